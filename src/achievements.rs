@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+
+use crate::{
+    game::achievements::{AchievementList, Achievements},
+    locale::Localized,
+    utils, AppState, GameAssets,
+};
+
+#[derive(Component)]
+struct AchievementsScreen;
+
+#[derive(Component)]
+enum AchievementsButton {
+    Back,
+}
+
+pub struct AchievementsScreenPlugin;
+
+impl Plugin for AchievementsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Achievements), setup_achievements)
+            .add_systems(
+                Update,
+                achievements_action.run_if(in_state(AppState::Achievements)),
+            )
+            .add_systems(
+                OnExit(AppState::Achievements),
+                utils::despawn_with::<AchievementsScreen>,
+            );
+    }
+}
+
+fn setup_achievements(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    achievements: Res<Achievements>,
+    achievement_list: Res<AchievementList>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            AchievementsScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                Localized::new("achievements-title"),
+            ));
+            for achievement in achievement_list.iter() {
+                let unlocked = achievements.is_unlocked(achievement);
+                let color = if unlocked { Color::GOLD } else { Color::GRAY };
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            margin: UiRect::all(Val::Px(8.0)),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "",
+                                TextStyle {
+                                    font: assets.font.clone(),
+                                    font_size: 36.0,
+                                    color,
+                                },
+                            ),
+                            Localized::new(achievement.name.clone()),
+                        ));
+                        let description = Localized::new("achievements-progress")
+                            .with_message("description", achievement.description.clone());
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "",
+                                TextStyle {
+                                    font: assets.font.clone(),
+                                    font_size: 20.0,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                            if unlocked {
+                                description.with_message("progress", "achievements-unlocked")
+                            } else {
+                                description.with(
+                                    "progress",
+                                    format!(
+                                        "{}/{}",
+                                        achievements.best(achievement),
+                                        achievement.condition.target()
+                                    ),
+                                )
+                            },
+                        ));
+                    });
+            }
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(250.0),
+                            height: Val::Px(65.0),
+                            margin: UiRect::all(Val::Px(20.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    AchievementsButton::Back,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font: assets.font.clone(),
+                                font_size: 40.0,
+                                color: Color::BLACK,
+                            },
+                        ),
+                        Localized::new("back"),
+                    ));
+                });
+        });
+}
+
+fn achievements_action(
+    interaction_query: Query<
+        (&Interaction, &AchievementsButton),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, achievements_button_action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match achievements_button_action {
+                AchievementsButton::Back => app_state.set(AppState::Menu),
+            }
+        }
+    }
+}
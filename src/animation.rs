@@ -0,0 +1,426 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{game::hazards::TimeScale, settings::Settings, AppState};
+
+/// The animations in `assets/animations.json`, which sets out the frames and timing of each one.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Clip {
+    ThrusterFlame,
+    DrillSpin,
+    ShieldShimmer,
+    Rock,
+    BrokenRock,
+    Ice,
+    SatelliteIdle,
+    SatelliteCharge,
+    LaserBeam,
+}
+
+impl Clip {
+    const ALL: [Self; 9] = [
+        Self::ThrusterFlame,
+        Self::DrillSpin,
+        Self::ShieldShimmer,
+        Self::Rock,
+        Self::BrokenRock,
+        Self::Ice,
+        Self::SatelliteIdle,
+        Self::SatelliteCharge,
+        Self::LaserBeam,
+    ];
+
+    const fn key(self) -> &'static str {
+        match self {
+            Self::ThrusterFlame => "thruster-flame",
+            Self::DrillSpin => "drill-spin",
+            Self::ShieldShimmer => "shield-shimmer",
+            Self::Rock => "rock",
+            Self::BrokenRock => "broken-rock",
+            Self::Ice => "ice",
+            Self::SatelliteIdle => "satellite-idle",
+            Self::SatelliteCharge => "satellite-charge",
+            Self::LaserBeam => "laser-beam",
+        }
+    }
+
+    /// Whether this animates a hazard, so plays on the game clock and slows down with the slow mode assist.
+    const fn is_hazard(self) -> bool {
+        !matches!(
+            self,
+            Self::ThrusterFlame | Self::DrillSpin | Self::ShieldShimmer
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct ClipData {
+    /// The image every frame comes from, unless the frame gives its own.
+    image: String,
+    /// How long each frame is shown for, unless the frame gives its own, in seconds.
+    frame_time: f32,
+    #[serde(default)]
+    looping: bool,
+    /// Whether the animation flashes, so it is held still when photosensitivity safe mode is on.
+    #[serde(default)]
+    flashing: bool,
+    /// The part of another image this clip is drawn over, for clips that animate part of a larger sprite.
+    overlay: Option<OverlayData>,
+    frames: Vec<FrameData>,
+}
+
+#[derive(Deserialize)]
+struct OverlayData {
+    image: String,
+    /// As x, y, width and height in pixels.
+    rect: [f32; 4],
+}
+
+#[derive(Deserialize)]
+struct FrameData {
+    image: Option<String>,
+    /// The part of the image to show, as x, y, width and height in pixels. Defaults to all of it.
+    rect: Option<[f32; 4]>,
+    #[serde(default)]
+    flip_x: bool,
+    #[serde(default)]
+    flip_y: bool,
+    time: Option<f32>,
+}
+
+struct Frame {
+    index: usize,
+    flip_x: bool,
+    flip_y: bool,
+    time: f32,
+}
+
+struct ClipSheet {
+    atlas: Handle<TextureAtlas>,
+    frames: Vec<Frame>,
+    looping: bool,
+    flashing: bool,
+    /// Where the middle of the overlay sits relative to the middle of the image it is drawn over, y up, and its size,
+    /// both as fractions of that image's size.
+    placement: Option<(Vec2, Vec2)>,
+}
+
+impl ClipSheet {
+    /// The frame shown when the animation is held still: the first of a loop, or the last of anything else.
+    fn resting_frame(&self) -> usize {
+        if self.looping {
+            0
+        } else {
+            self.frames.len() - 1
+        }
+    }
+}
+
+/// Every clip, built into texture atlases once the images they use have loaded.
+#[derive(Resource, Default)]
+pub struct AnimationLibrary(HashMap<Clip, ClipSheet>);
+
+impl AnimationLibrary {
+    /// A sprite sheet showing the first frame of `clip`, to be spawned along with an [`Animation`] playing it.
+    pub fn sheet(
+        &self,
+        clip: Clip,
+        sprite: TextureAtlasSprite,
+        transform: Transform,
+    ) -> SpriteSheetBundle {
+        let Some(sheet) = self.0.get(&clip) else {
+            return SpriteSheetBundle {
+                sprite,
+                transform,
+                ..default()
+            };
+        };
+        let frame = &sheet.frames[0];
+        SpriteSheetBundle {
+            sprite: TextureAtlasSprite {
+                index: frame.index,
+                flip_x: frame.flip_x,
+                flip_y: frame.flip_y,
+                ..sprite
+            },
+            texture_atlas: sheet.atlas.clone(),
+            transform,
+            ..default()
+        }
+    }
+
+    /// Where `clip` sits over the image it animates part of and how big it is, as fractions of that image's size with y up.
+    pub fn placement(&self, clip: Clip) -> Option<(Vec2, Vec2)> {
+        self.0.get(&clip).and_then(|sheet| sheet.placement)
+    }
+
+    /// Whether `clip` is held on its resting frame instead of playing.
+    pub fn is_held(&self, clip: Clip, settings: &Settings) -> bool {
+        self.0.get(&clip).is_some_and(|sheet| {
+            settings.reduced_motion || (settings.photosensitivity_safe && sheet.flashing)
+        })
+    }
+}
+
+/// Plays a clip on a sprite sheet.
+#[derive(Component)]
+pub struct Animation {
+    clip: Clip,
+    frame: usize,
+    /// How long the current frame has been shown for, in seconds.
+    elapsed: f32,
+    finished: bool,
+}
+
+impl Animation {
+    pub const fn new(clip: Clip) -> Self {
+        Self {
+            clip,
+            frame: 0,
+            elapsed: 0.0,
+            finished: false,
+        }
+    }
+
+    pub const fn clip(&self) -> Clip {
+        self.clip
+    }
+
+    /// Whether a clip that doesn't loop has reached its last frame, or is being held on it.
+    pub const fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Starts `clip` from the beginning, even if it is already playing.
+    pub fn play(&mut self, clip: Clip) {
+        *self = Self::new(clip);
+    }
+
+    /// Switches to `clip`, leaving it be if it is already playing.
+    pub fn switch(&mut self, clip: Clip) {
+        if self.clip != clip {
+            self.play(clip);
+        }
+    }
+}
+
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AnimationLibrary>()
+            .add_systems(OnExit(AppState::Loading), build_library)
+            .add_systems(Update, animate);
+    }
+}
+
+fn clip_data() -> Result<HashMap<String, ClipData>, serde_json::Error> {
+    serde_json::from_str(include_str!("../assets/animations.json"))
+}
+
+/// Every image used by a clip or overlay in `assets/animations.json`, for `GameAssets` to load up front.
+pub fn sheet_images() -> Vec<String> {
+    let data = match clip_data() {
+        Ok(data) => data,
+        Err(error) => {
+            error!("Failed to parse animations: {}", error);
+            return Vec::new();
+        }
+    };
+
+    let mut images = BTreeSet::new();
+    for clip_data in data.into_values() {
+        images.extend(clip_data.overlay.map(|overlay| overlay.image));
+        images.extend(clip_data.frames.into_iter().filter_map(|frame| frame.image));
+        images.insert(clip_data.image);
+    }
+    images.into_iter().collect()
+}
+
+fn build_library(
+    mut library: ResMut<AnimationLibrary>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    mut atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    let data = match clip_data() {
+        Ok(data) => data,
+        Err(error) => {
+            error!("Failed to parse animations: {}", error);
+            return;
+        }
+    };
+
+    for clip in Clip::ALL {
+        let Some(clip_data) = data.get(clip.key()) else {
+            error!("No animation for {}", clip.key());
+            continue;
+        };
+        match build_sheet(clip_data, &asset_server, &mut images, &mut atlases) {
+            Ok(sheet) => {
+                library.0.insert(clip, sheet);
+            }
+            Err(error) => error!("Failed to build animation {}: {}", clip.key(), error),
+        }
+    }
+}
+
+/// Stitches every image a clip uses into one atlas, then adds any frames that only show part of an image.
+fn build_sheet(
+    clip_data: &ClipData,
+    asset_server: &AssetServer,
+    images: &mut Assets<Image>,
+    atlases: &mut Assets<TextureAtlas>,
+) -> Result<ClipSheet, String> {
+    if clip_data.frames.is_empty() {
+        return Err("it has no frames".to_string());
+    }
+
+    // Only images already loaded by `GameAssets` can be used, so these handles are always ready.
+    let image_of = |frame: &FrameData| -> Handle<Image> {
+        asset_server.load(frame.image.as_ref().unwrap_or(&clip_data.image).clone())
+    };
+
+    let mut builder = TextureAtlasBuilder::default();
+    let mut added = HashSet::new();
+    for frame in &clip_data.frames {
+        let handle = image_of(frame);
+        if !added.insert(handle.id()) {
+            continue;
+        }
+        let image = images
+            .get(&handle)
+            .ok_or_else(|| format!("{:?} isn't loaded", asset_server.get_path(handle.id())))?;
+        builder.add_texture(handle.id(), image);
+    }
+    let mut atlas = builder.finish(images).map_err(|error| error.to_string())?;
+
+    let mut frames = Vec::new();
+    for frame in &clip_data.frames {
+        let id = image_of(frame).id();
+        let whole = atlas.get_texture_index(id).expect("every image was added");
+        let index = match frame.rect {
+            Some([x, y, width, height]) => {
+                let min = atlas.textures[whole].min + Vec2::new(x, y);
+                atlas.add_texture(Rect::from_corners(min, min + Vec2::new(width, height)))
+            }
+            None => whole,
+        };
+
+        let time = frame.time.unwrap_or(clip_data.frame_time);
+        if time <= 0.0 {
+            return Err("frames must be shown for some time".to_string());
+        }
+        frames.push(Frame {
+            index,
+            flip_x: frame.flip_x,
+            flip_y: frame.flip_y,
+            time,
+        });
+    }
+
+    let placement = match &clip_data.overlay {
+        Some(overlay) => {
+            let image = images
+                .get(asset_server.load::<Image>(overlay.image.clone()))
+                .ok_or_else(|| format!("{} isn't loaded", overlay.image))?;
+            let image_size = image.size_f32();
+            let [x, y, width, height] = overlay.rect;
+            let offset = Vec2::new(x + width / 2.0, y + height / 2.0) - image_size / 2.0;
+            Some((
+                Vec2::new(offset.x, -offset.y) / image_size,
+                Vec2::new(width, height) / image_size,
+            ))
+        }
+        None => None,
+    };
+    Ok(ClipSheet {
+        atlas: atlases.add(atlas),
+        frames,
+        looping: clip_data.looping,
+        flashing: clip_data.flashing,
+        placement,
+    })
+}
+
+fn animate(
+    library: Res<AnimationLibrary>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    settings: Res<Settings>,
+    mut animations: Query<(
+        &mut Animation,
+        &mut TextureAtlasSprite,
+        &mut Handle<TextureAtlas>,
+    )>,
+) {
+    for (mut animation, mut sprite, mut atlas) in animations.iter_mut() {
+        let Some(sheet) = library.0.get(&animation.clip) else {
+            continue;
+        };
+
+        if library.is_held(animation.clip, &settings) {
+            animation.frame = sheet.resting_frame();
+            animation.finished = !sheet.looping;
+        } else {
+            animation.elapsed += if animation.clip.is_hazard() {
+                time_scale.delta_seconds(&time)
+            } else {
+                time.delta_seconds()
+            };
+            while animation.elapsed >= sheet.frames[animation.frame].time {
+                if animation.frame + 1 < sheet.frames.len() {
+                    animation.elapsed -= sheet.frames[animation.frame].time;
+                    animation.frame += 1;
+                } else if sheet.looping {
+                    animation.elapsed -= sheet.frames[animation.frame].time;
+                    animation.frame = 0;
+                } else {
+                    animation.elapsed = sheet.frames[animation.frame].time;
+                    animation.finished = true;
+                    break;
+                }
+            }
+        }
+
+        let frame = &sheet.frames[animation.frame];
+        if *atlas != sheet.atlas {
+            *atlas = sheet.atlas.clone();
+        }
+        sprite.index = frame.index;
+        sprite.flip_x = frame.flip_x;
+        sprite.flip_y = frame.flip_y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_clip_is_in_the_data() {
+        let data = clip_data().unwrap();
+        for clip in Clip::ALL {
+            assert!(data.contains_key(clip.key()), "no data for {}", clip.key());
+        }
+    }
+
+    #[test]
+    fn sheet_images_cover_every_clip_and_exist() {
+        let images = sheet_images();
+        for clip_data in clip_data().unwrap().values() {
+            assert!(images.contains(&clip_data.image));
+            if let Some(overlay) = &clip_data.overlay {
+                assert!(images.contains(&overlay.image));
+            }
+        }
+        for image in images {
+            let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("assets")
+                .join(&image);
+            assert!(path.exists(), "{image} is missing");
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{locale::Localized, save, AppState, GameAssets};
+
+use super::{
+    hazards::HazardType,
+    running,
+    score::{update_score, Score},
+    spaceship::{handle_hits, Health, HitOutcomeEvent, MaxHealth, Spaceship},
+};
+
+const TOAST_DURATION: f32 = 3.0;
+
+/// What has to happen during a single run to unlock an achievement.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    /// Block this many lasers in a row.
+    LaserStreak(u32),
+    SurviveSeconds(u32),
+    /// Reach this score without ever losing health.
+    ScoreWithoutDamage(u32),
+    ReachScore(u32),
+    CrateAtFullHealth,
+}
+
+impl Condition {
+    pub const fn target(self) -> u32 {
+        match self {
+            Self::LaserStreak(target)
+            | Self::SurviveSeconds(target)
+            | Self::ScoreWithoutDamage(target)
+            | Self::ReachScore(target) => target,
+            Self::CrateAtFullHealth => 1,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Achievement {
+    /// Used as the key in the save file, so it must never change.
+    pub id: String,
+    /// The key of its name in the translation files.
+    pub name: String,
+    /// The key of its description in the translation files.
+    pub description: String,
+    pub condition: Condition,
+}
+
+/// Every achievement, in the order they are listed, from `assets/achievements.json`.
+#[derive(Resource, Deref)]
+pub struct AchievementList(Vec<Achievement>);
+
+impl Default for AchievementList {
+    fn default() -> Self {
+        match serde_json::from_str(include_str!("../../assets/achievements.json")) {
+            Ok(achievements) => Self(achievements),
+            Err(error) => {
+                error!("Failed to parse achievements: {}", error);
+                Self(Vec::new())
+            }
+        }
+    }
+}
+
+/// Which achievements have been unlocked, and the best progress towards each one.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct Achievements {
+    unlocked: Vec<String>,
+    best: HashMap<String, u32>,
+}
+
+impl Achievements {
+    pub fn is_unlocked(&self, achievement: &Achievement) -> bool {
+        self.unlocked.contains(&achievement.id)
+    }
+
+    pub fn best(&self, achievement: &Achievement) -> u32 {
+        self.best.get(&achievement.id).copied().unwrap_or(0)
+    }
+}
+
+/// What has happened so far in the current run.
+#[derive(Resource, Default)]
+struct RunProgress {
+    laser_streak: u32,
+    run_time: f32,
+    score: u32,
+    damaged: bool,
+    full_health_crates: u32,
+    was_full_health: bool,
+    last_health: Option<u32>,
+}
+
+impl RunProgress {
+    fn value(&self, condition: Condition) -> u32 {
+        match condition {
+            Condition::LaserStreak(_) => self.laser_streak,
+            Condition::SurviveSeconds(_) => self.run_time as u32,
+            Condition::ScoreWithoutDamage(_) => {
+                if self.damaged {
+                    0
+                } else {
+                    self.score
+                }
+            }
+            Condition::ReachScore(_) => self.score,
+            Condition::CrateAtFullHealth => self.full_health_crates,
+        }
+    }
+}
+
+#[derive(Component)]
+struct ToastContainer;
+
+#[derive(Component)]
+struct Toast(Timer);
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(save::load::<Achievements>("achievements"))
+            .init_resource::<AchievementList>()
+            .init_resource::<RunProgress>()
+            .add_systems(OnExit(AppState::Splash), spawn_toast_container)
+            .add_systems(OnEnter(AppState::Playing), reset_run_progress)
+            .add_systems(
+                Update,
+                (
+                    track_hits,
+                    track_score,
+                    track_health,
+                    track_time,
+                    check_achievements,
+                )
+                    .chain()
+                    .after(handle_hits)
+                    .after(update_score)
+                    .run_if(running),
+            )
+            .add_systems(Update, update_toasts)
+            .add_systems(OnExit(AppState::Playing), save_achievements);
+    }
+}
+
+fn reset_run_progress(mut run_progress: ResMut<RunProgress>) {
+    *run_progress = RunProgress::default();
+}
+
+fn track_hits(
+    mut outcome_events: EventReader<HitOutcomeEvent>,
+    mut run_progress: ResMut<RunProgress>,
+) {
+    for event in outcome_events.read() {
+        match (event.hit.hazard_type, event.success) {
+            (HazardType::Laser, true) => run_progress.laser_streak += 1,
+            (HazardType::Laser, false) => run_progress.laser_streak = 0,
+            // `handle_hits` has already healed the spaceship by the time this runs, so check last frame's health instead.
+            (HazardType::Crate, true) if run_progress.was_full_health => {
+                run_progress.full_health_crates += 1;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn track_score(score: Res<Score>, mut run_progress: ResMut<RunProgress>) {
+    run_progress.score = score.score as u32;
+}
+
+fn track_health(
+    spaceships: Query<(&Health, &MaxHealth), With<Spaceship>>,
+    mut run_progress: ResMut<RunProgress>,
+) {
+    let Ok((health, max_health)) = spaceships.get_single() else {
+        return;
+    };
+    if run_progress
+        .last_health
+        .is_some_and(|last_health| health.0 < last_health)
+    {
+        run_progress.damaged = true;
+    }
+    run_progress.last_health = Some(health.0);
+    run_progress.was_full_health = health.0 >= max_health.0;
+}
+
+fn track_time(mut run_progress: ResMut<RunProgress>, time: Res<Time>) {
+    run_progress.run_time += time.delta_seconds();
+}
+
+fn check_achievements(
+    mut commands: Commands,
+    run_progress: Res<RunProgress>,
+    mut achievements: ResMut<Achievements>,
+    toast_containers: Query<Entity, With<ToastContainer>>,
+    assets: Res<GameAssets>,
+    achievement_list: Res<AchievementList>,
+) {
+    for achievement in achievement_list.iter() {
+        let value = run_progress
+            .value(achievement.condition)
+            .min(achievement.condition.target());
+        if value > achievements.best(achievement) {
+            achievements.best.insert(achievement.id.clone(), value);
+        }
+
+        if value < achievement.condition.target() || achievements.is_unlocked(achievement) {
+            continue;
+        }
+        achievements.unlocked.push(achievement.id.clone());
+        info!("Unlocked achievement {}", achievement.id);
+
+        let Ok(container) = toast_containers.get_single() else {
+            continue;
+        };
+        commands.entity(container).with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 30.0,
+                        color: Color::GOLD,
+                    },
+                ),
+                Localized::new("achievement-unlocked")
+                    .with_message("name", achievement.name.clone()),
+                Toast(Timer::from_seconds(TOAST_DURATION, TimerMode::Once)),
+            ));
+        });
+    }
+}
+
+fn save_achievements(achievements: Res<Achievements>) {
+    save::store("achievements", &*achievements);
+}
+
+fn spawn_toast_container(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                top: Val::Px(60.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            z_index: ZIndex::Global(10),
+            ..default()
+        },
+        ToastContainer,
+    ));
+}
+
+fn update_toasts(
+    mut commands: Commands,
+    mut toasts: Query<(Entity, &mut Toast, &mut Text)>,
+    time: Res<Time>,
+) {
+    for (entity, mut toast, mut text) in toasts.iter_mut() {
+        toast.0.tick(time.delta());
+        text.sections[0]
+            .style
+            .color
+            .set_a(toast.0.percent_left().min(0.5) * 2.0);
+        if toast.0.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game::{hazards::HitEvent, layout::Tool},
+        utils::Direction,
+    };
+
+    fn collect_crate(world: &mut World) {
+        let source = world.spawn_empty().id();
+        world.send_event(HitOutcomeEvent {
+            hit: HitEvent {
+                hazard_type: HazardType::Crate,
+                from_direction: Direction::Up,
+                spawned_at: 0.0,
+                source,
+                barrage: None,
+            },
+            tool: Tool::Cockpit,
+            success: true,
+            damage: 0,
+        });
+    }
+
+    /// Runs a frame of tracking, after `handle_hits` has left the spaceship at `health`.
+    fn track(world: &mut World, spaceship: Entity, health: u32) {
+        world.get_mut::<Health>(spaceship).unwrap().0 = health;
+        let mut schedule = Schedule::default();
+        schedule.add_systems((track_hits, track_health).chain());
+        schedule.run(world);
+    }
+
+    fn full_health_crates(max_health_before: u32) -> u32 {
+        let mut world = World::new();
+        world.init_resource::<Events<HitOutcomeEvent>>();
+        world.init_resource::<RunProgress>();
+        let spaceship = world.spawn((Spaceship, Health(0), MaxHealth(5))).id();
+
+        track(&mut world, spaceship, max_health_before);
+        collect_crate(&mut world);
+        track(&mut world, spaceship, 5);
+        world.resource::<RunProgress>().full_health_crates
+    }
+
+    #[test]
+    fn crates_that_heal_up_to_full_health_do_not_count() {
+        assert_eq!(full_health_crates(4), 0);
+    }
+
+    #[test]
+    fn crates_at_full_health_count() {
+        assert_eq!(full_health_crates(5), 1);
+    }
+}
@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{hazards::HazardType, layout::Tool};
+
+/// The preset chosen on the main menu before a run.
+#[derive(Resource, Default, Debug, Hash, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Insane,
+}
+
+pub struct DifficultyPreset {
+    pub starting_health: u32,
+    pub max_health: u32,
+    /// Seconds between hazards at the start of a run, before the score speeds things up.
+    pub base_interval: f32,
+    /// Pixels per second.
+    pub hazard_speed: f32,
+    /// Seconds a satellite spends charging before it fires.
+    pub laser_charge_time: f32,
+    /// The tool that has to meet a missile once it has finished switching sides.
+    pub missile_counter: Tool,
+    pub hazards: &'static [HazardType],
+}
+
+impl Difficulty {
+    /// The key of its name in the translation files.
+    pub const fn name_key(self) -> &'static str {
+        match self {
+            Self::Easy => "difficulty-easy",
+            Self::Normal => "difficulty-normal",
+            Self::Hard => "difficulty-hard",
+            Self::Insane => "difficulty-insane",
+        }
+    }
+
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Easy => Self::Normal,
+            Self::Normal => Self::Hard,
+            Self::Hard => Self::Insane,
+            Self::Insane => Self::Easy,
+        }
+    }
+
+    pub const fn preset(self) -> DifficultyPreset {
+        match self {
+            Self::Easy => DifficultyPreset {
+                starting_health: 5,
+                max_health: 7,
+                base_interval: 2.5,
+                hazard_speed: 160.0,
+                laser_charge_time: 0.8,
+                missile_counter: Tool::Shield,
+                hazards: &[
+                    HazardType::Rock,
+                    HazardType::Ice,
+                    HazardType::Laser,
+                    HazardType::Crate,
+                ],
+            },
+            Self::Normal => DifficultyPreset {
+                starting_health: 3,
+                max_health: 5,
+                base_interval: 2.0,
+                hazard_speed: 200.0,
+                laser_charge_time: 0.5,
+                missile_counter: Tool::Shield,
+                hazards: &HazardType::ALL,
+            },
+            Self::Hard => DifficultyPreset {
+                starting_health: 3,
+                max_health: 3,
+                base_interval: 1.5,
+                hazard_speed: 240.0,
+                laser_charge_time: 0.4,
+                missile_counter: Tool::Shield,
+                hazards: &HazardType::ALL,
+            },
+            Self::Insane => DifficultyPreset {
+                starting_health: 1,
+                max_health: 1,
+                base_interval: 1.1,
+                hazard_speed: 280.0,
+                laser_charge_time: 0.3,
+                missile_counter: Tool::Thruster,
+                hazards: &[
+                    HazardType::Rock,
+                    HazardType::Ice,
+                    HazardType::Laser,
+                    HazardType::Missile,
+                    HazardType::Scrambler,
+                ],
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::layout::ShipLayout;
+
+    #[test]
+    fn every_playable_loadout_can_counter_missiles() {
+        let loadouts = [
+            ShipLayout([Tool::Drill, Tool::Thruster, Tool::Shield, Tool::Cockpit]),
+            ShipLayout([Tool::Drill, Tool::Thruster, Tool::Reflector, Tool::Magnet]),
+        ];
+        for difficulty in [
+            Difficulty::Easy,
+            Difficulty::Normal,
+            Difficulty::Hard,
+            Difficulty::Insane,
+        ] {
+            let counter = difficulty.preset().missile_counter;
+            for loadout in loadouts {
+                assert!(loadout.is_playable());
+                assert!(
+                    loadout.0.contains(&counter)
+                        || (counter == Tool::Shield && loadout.0.contains(&Tool::Reflector)),
+                    "{difficulty:?} missiles need a {counter:?}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use bevy::prelude::*;
+use rand::{distributions::Standard, prelude::Distribution, Rng};
+
+use crate::{
+    locale::Localized,
+    palette::{Palette, Role},
+    settings::Settings,
+    AppState, GameAssets,
+};
+
+use super::{running, Game};
+
+/// A bonus carried by some crates, which always comes with a drawback.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PowerUp {
+    SlowMotion,
+    DoubleShield,
+    DoubleScore,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Drawback {
+    ReversedControls,
+    HiddenHud,
+    FasterHazards,
+}
+
+impl Distribution<PowerUp> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> PowerUp {
+        match rng.gen_range(0..3) {
+            0 => PowerUp::SlowMotion,
+            1 => PowerUp::DoubleShield,
+            _ => PowerUp::DoubleScore,
+        }
+    }
+}
+
+impl PowerUp {
+    pub const fn drawback(self) -> Drawback {
+        match self {
+            Self::SlowMotion => Drawback::ReversedControls,
+            Self::DoubleShield => Drawback::HiddenHud,
+            Self::DoubleScore => Drawback::FasterHazards,
+        }
+    }
+
+    pub const fn duration(self) -> f32 {
+        match self {
+            Self::SlowMotion => 6.0,
+            Self::DoubleShield => 8.0,
+            Self::DoubleScore => 10.0,
+        }
+    }
+
+    /// The tint of crates carrying this power-up.
+    pub const fn color(self, palette: Palette) -> Color {
+        palette.color(match self {
+            Self::SlowMotion => Role::SlowMotion,
+            Self::DoubleShield => Role::DoubleShield,
+            Self::DoubleScore => Role::DoubleScore,
+        })
+    }
+
+    /// The key of its name in the translation files.
+    const fn name_key(self) -> &'static str {
+        match self {
+            Self::SlowMotion => "power-up-slow-motion",
+            Self::DoubleShield => "power-up-double-shield",
+            Self::DoubleScore => "power-up-double-score",
+        }
+    }
+}
+
+impl Drawback {
+    /// The key of its name in the translation files.
+    const fn name_key(self) -> &'static str {
+        match self {
+            Self::ReversedControls => "drawback-reversed-controls",
+            Self::HiddenHud => "drawback-hidden-hud",
+            Self::FasterHazards => "drawback-faster-hazards",
+        }
+    }
+}
+
+/// The power-ups currently active, along with how long they have left.
+#[derive(Resource, Default)]
+pub struct ActiveEffects(Vec<(PowerUp, Timer)>);
+
+impl ActiveEffects {
+    pub fn activate(&mut self, power_up: PowerUp, duration_scale: f32) {
+        let timer = Timer::from_seconds(power_up.duration() * duration_scale, TimerMode::Once);
+        match self.0.iter_mut().find(|(active, _)| *active == power_up) {
+            Some((_, active_timer)) => *active_timer = timer,
+            None => self.0.push((power_up, timer)),
+        }
+    }
+
+    pub fn has(&self, power_up: PowerUp) -> bool {
+        self.0.iter().any(|(active, _)| *active == power_up)
+    }
+
+    pub fn has_drawback(&self, drawback: Drawback) -> bool {
+        self.0
+            .iter()
+            .any(|(active, _)| active.drawback() == drawback)
+    }
+
+    /// Multiplier for the speed of incoming hazards.
+    pub fn speed_scale(&self) -> f32 {
+        let mut scale = 1.0;
+        if self.has(PowerUp::SlowMotion) {
+            scale *= 0.5;
+        }
+        if self.has_drawback(Drawback::FasterHazards) {
+            scale *= 1.4;
+        }
+        scale
+    }
+
+    /// How many points each score event is worth.
+    pub fn score_multiplier(&self) -> usize {
+        if self.has(PowerUp::DoubleScore) {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+/// Parts of the HUD which are hidden by the [`Drawback::HiddenHud`] drawback.
+#[derive(Component)]
+pub struct HudElement;
+
+#[derive(Component)]
+struct EffectsDisplay;
+
+/// The line of the effects display for one active power-up.
+#[derive(Component)]
+struct EffectRow(PowerUp);
+
+pub struct EffectsPlugin;
+
+impl Plugin for EffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveEffects>()
+            .add_systems(
+                OnEnter(AppState::Playing),
+                (reset_effects, spawn_effects_display),
+            )
+            .add_systems(
+                Update,
+                (tick_effects, update_effects_display)
+                    .chain()
+                    .run_if(running),
+            )
+            .add_systems(Update, toggle_hud)
+            .add_systems(OnExit(AppState::Playing), reset_effects);
+    }
+}
+
+fn reset_effects(mut effects: ResMut<ActiveEffects>) {
+    effects.0.clear();
+}
+
+fn tick_effects(mut effects: ResMut<ActiveEffects>, time: Res<Time>) {
+    for (_, timer) in &mut effects.0 {
+        timer.tick(time.delta());
+    }
+    effects.0.retain(|(_, timer)| !timer.finished());
+}
+
+fn spawn_effects_display(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(10.0),
+                top: Val::Px(10.0),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            ..default()
+        },
+        EffectsDisplay,
+        Game,
+    ));
+}
+
+fn effect_text(power_up: PowerUp, timer: &Timer) -> Localized {
+    Localized::new("hud-effect")
+        .with_message("power_up", power_up.name_key())
+        .with("seconds", format!("{:.1}", timer.remaining_secs()))
+        .with_message("drawback", power_up.drawback().name_key())
+}
+
+/// Adds a row for each power-up as it starts and removes it once it runs out, updating the rest in place.
+fn update_effects_display(
+    mut commands: Commands,
+    effects: Res<ActiveEffects>,
+    effects_display_query: Query<Entity, With<EffectsDisplay>>,
+    mut rows: Query<(Entity, &EffectRow, &mut Localized)>,
+    assets: Res<GameAssets>,
+    settings: Res<Settings>,
+) {
+    let Ok(effects_display) = effects_display_query.get_single() else {
+        return;
+    };
+
+    for (entity, row, mut localized) in rows.iter_mut() {
+        match effects.0.iter().find(|(active, _)| *active == row.0) {
+            Some((power_up, timer)) => {
+                localized.set_if_neq(effect_text(*power_up, timer));
+            }
+            None => commands.entity(entity).despawn_recursive(),
+        }
+    }
+
+    for (power_up, timer) in &effects.0 {
+        if rows.iter().any(|(_, row, _)| row.0 == *power_up) {
+            continue;
+        }
+        commands.entity(effects_display).with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 30.0,
+                        color: power_up.color(settings.palette),
+                    },
+                ),
+                effect_text(*power_up, timer),
+                EffectRow(*power_up),
+            ));
+        });
+    }
+}
+
+fn toggle_hud(effects: Res<ActiveEffects>, mut hud: Query<&mut Visibility, With<HudElement>>) {
+    let visibility = if effects.has_drawback(Drawback::HiddenHud) {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    for mut hud_visibility in hud.iter_mut() {
+        hud_visibility.set_if_neq(visibility);
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        running,
+        spaceship::{Health, HitOutcomeEvent, Spaceship},
+    },
+    utils::Direction,
+    AppState,
+};
+
+use super::{GameClock, HazardMix, HazardType};
+
+/// How many recent hazards are considered when measuring the player's hit rate.
+const OUTCOME_WINDOW: usize = 12;
+/// How many recent reaction times are averaged.
+const REACTION_WINDOW: usize = 8;
+/// How many health samples (one per evaluation) make up the health trend.
+const HEALTH_WINDOW: usize = 4;
+/// Seconds between difficulty adjustments.
+const EVALUATION_PERIOD: f32 = 5.0;
+
+const MIN_LEVEL: f32 = 0.6;
+const MAX_LEVEL: f32 = 1.6;
+
+/// Tunes the hazard spawner to keep the player close to a target challenge level.
+///
+/// When disabled, every scale is neutral and the game is driven by the score alone.
+#[derive(Resource)]
+pub struct AdaptiveDifficulty {
+    pub enabled: bool,
+    /// The performance (roughly the fraction of hazards handled) the player should sit at.
+    pub target: f32,
+    /// Current intensity, where 1.0 is the unmodified game.
+    level: f32,
+    outcomes: VecDeque<bool>,
+    reaction_times: VecDeque<f32>,
+    health_samples: VecDeque<u32>,
+    last_turn: f32,
+    timer: Timer,
+}
+
+impl Default for AdaptiveDifficulty {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target: 0.8,
+            level: 1.0,
+            outcomes: VecDeque::new(),
+            reaction_times: VecDeque::new(),
+            health_samples: VecDeque::new(),
+            last_turn: 0.0,
+            timer: Timer::from_seconds(EVALUATION_PERIOD, TimerMode::Repeating),
+        }
+    }
+}
+
+impl AdaptiveDifficulty {
+    fn level(&self) -> f32 {
+        if self.enabled {
+            self.level
+        } else {
+            1.0
+        }
+    }
+
+    /// Multiplier for the speed of incoming hazards.
+    pub fn speed_scale(&self) -> f32 {
+        (self.level() - 1.0).mul_add(0.5, 1.0)
+    }
+
+    /// Multiplier for the time between hazard spawns.
+    pub fn interval_scale(&self) -> f32 {
+        self.level().recip()
+    }
+
+    /// Shifts the mix towards lasers and missiles and away from crates as the level rises.
+    pub fn hazard_mix(&self) -> HazardMix {
+        let level = self.level();
+        HazardMix([
+            4,
+            3,
+            (2.0 * level).round() as u32,
+            if level < 1.0 { 2 } else { 1 },
+            level.round() as u32,
+            1,
+        ])
+    }
+
+    fn hit_rate(&self) -> Option<f32> {
+        if self.outcomes.is_empty() {
+            return None;
+        }
+        let successes = self.outcomes.iter().filter(|&&success| success).count();
+        Some(successes as f32 / self.outcomes.len() as f32)
+    }
+
+    fn health_trend(&self) -> i32 {
+        match (self.health_samples.front(), self.health_samples.back()) {
+            (Some(&first), Some(&last)) => last as i32 - first as i32,
+            _ => 0,
+        }
+    }
+
+    fn average_reaction_time(&self) -> Option<f32> {
+        if self.reaction_times.is_empty() {
+            return None;
+        }
+        Some(self.reaction_times.iter().sum::<f32>() / self.reaction_times.len() as f32)
+    }
+
+    fn reset(&mut self) {
+        *self = Self {
+            enabled: self.enabled,
+            target: self.target,
+            ..default()
+        };
+    }
+}
+
+pub struct AdaptivePlugin;
+
+impl Plugin for AdaptivePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AdaptiveDifficulty>()
+            .add_systems(OnEnter(AppState::Playing), reset_adaptive)
+            .add_systems(
+                Update,
+                (track_turns, track_outcomes, adjust_difficulty)
+                    .chain()
+                    .run_if(running)
+                    .run_if(|adaptive: Res<AdaptiveDifficulty>| adaptive.enabled),
+            );
+    }
+}
+
+fn reset_adaptive(mut adaptive: ResMut<AdaptiveDifficulty>) {
+    adaptive.reset();
+}
+
+fn track_turns(
+    mut adaptive: ResMut<AdaptiveDifficulty>,
+    spaceships: Query<(), (Changed<Direction>, With<Spaceship>)>,
+    clock: Res<GameClock>,
+) {
+    if !spaceships.is_empty() {
+        adaptive.last_turn = **clock;
+    }
+}
+
+fn track_outcomes(
+    mut adaptive: ResMut<AdaptiveDifficulty>,
+    mut outcome_events: EventReader<HitOutcomeEvent>,
+) {
+    for event in outcome_events.read() {
+        // Missing a crate costs nothing, so it says little about how hard the game is.
+        if event.hit.hazard_type == HazardType::Crate && !event.success {
+            continue;
+        }
+
+        adaptive.outcomes.push_back(event.success);
+        if adaptive.outcomes.len() > OUTCOME_WINDOW {
+            adaptive.outcomes.pop_front();
+        }
+
+        // Only count turns made after the hazard appeared, otherwise the ship was already in place.
+        if event.success && adaptive.last_turn > event.hit.spawned_at {
+            let reaction_time = adaptive.last_turn - event.hit.spawned_at;
+            adaptive.reaction_times.push_back(reaction_time);
+            if adaptive.reaction_times.len() > REACTION_WINDOW {
+                adaptive.reaction_times.pop_front();
+            }
+        }
+    }
+}
+
+fn adjust_difficulty(
+    mut adaptive: ResMut<AdaptiveDifficulty>,
+    health: Query<&Health, With<Spaceship>>,
+    time: Res<Time>,
+) {
+    if !adaptive.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    if let Ok(health) = health.get_single() {
+        adaptive.health_samples.push_back(health.0);
+        if adaptive.health_samples.len() > HEALTH_WINDOW {
+            adaptive.health_samples.pop_front();
+        }
+    }
+
+    let Some(hit_rate) = adaptive.hit_rate() else {
+        return;
+    };
+    let health_trend = adaptive.health_trend();
+    let reaction_time = adaptive.average_reaction_time();
+
+    let health_bonus = (health_trend as f32 * 0.1).clamp(-0.2, 0.2);
+    let reaction_bonus = reaction_time.map_or(0.0, |time| ((0.9 - time) * 0.25).clamp(-0.1, 0.1));
+    let performance = hit_rate + health_bonus + reaction_bonus;
+
+    let previous_level = adaptive.level;
+    adaptive.level = (performance - adaptive.target)
+        .mul_add(0.5, previous_level)
+        .clamp(MIN_LEVEL, MAX_LEVEL);
+
+    info!(
+        target: "suborbital::adaptive",
+        "hit_rate={hit_rate:.2} health_trend={health_trend} reaction_time={reaction_time:?} \
+         performance={performance:.2} level={previous_level:.2}->{:.2} speed_scale={:.2} \
+         interval_scale={:.2} mix={:?}",
+        adaptive.level,
+        adaptive.speed_scale(),
+        adaptive.interval_scale(),
+        adaptive.hazard_mix().0,
+    );
+}
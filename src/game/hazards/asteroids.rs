@@ -3,104 +3,340 @@ use bevy::prelude::*;
 
 use rand::prelude::*;
 
-use crate::{game::Game, utils::Direction, AppState, GameAssets};
+use crate::{
+    animation::{Animation, AnimationLibrary, Clip},
+    game::{
+        running,
+        spaceship::{handle_hits, HitOutcomeEvent},
+        Game,
+    },
+    palette::Role,
+    settings::Settings,
+    utils::{Direction, DirectionMode},
+    GameAssets,
+};
 
-use super::{HazardType, HitEvent};
+use super::{GameClock, HazardSpeed, HazardType, HitEvent, RunRng, SpawnedAt, TimeScale};
+
+const FRAGMENT_RANGE: f32 = 320.0;
+const DEBRIS_PIECES: usize = 4;
 
 pub struct AsteroidsPlugin;
 
 impl Plugin for AsteroidsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, update_asteroids.run_if(in_state(AppState::Playing)));
+        app.add_systems(
+            Update,
+            (
+                update_asteroids.before(handle_hits),
+                update_bouncing_asteroids,
+                break_asteroids.after(handle_hits),
+                update_debris,
+            )
+                .run_if(running),
+        );
     }
 }
 
 #[derive(Component)]
 struct Asteroid;
 
+/// How many smaller rocks this asteroid splits into when drilled.
+#[derive(Component)]
+struct Fragments(u32);
+
+/// A fragment being thrown back out to the side it will return from.
+#[derive(Component)]
+struct Bouncing(Timer);
+
+/// An asteroid that has reached the spaceship and is waiting to find out if it was drilled.
+#[derive(Component)]
+struct Impacted;
+
+#[derive(Component)]
+struct Debris {
+    velocity: Vec3,
+    timer: Timer,
+}
+
 #[derive(Bundle)]
 struct AsteroidBundle {
     asteroid_marker: Asteroid,
     game_marker: Game,
     direction: Direction,
     hazard_type: HazardType,
+    spawned_at: SpawnedAt,
+    fragments: Fragments,
+    animation: Animation,
     #[bundle()]
-    sprite: SpriteBundle,
+    sprite: SpriteSheetBundle,
 }
 
 pub enum SpawnAsteroidCommand {
     Rock,
     Ice,
+    /// A large rock that splits into `fragments` smaller rocks when drilled.
+    LargeRock {
+        fragments: u32,
+    },
+    /// A small rock thrown out from `position` that comes back in from `direction`.
+    Fragment {
+        direction: Direction,
+        position: Vec3,
+    },
 }
 
 impl Command for SpawnAsteroidCommand {
     fn apply(self, world: &mut World) {
-        let mut rng = thread_rng();
-        let direction: Direction = rng.gen();
+        let direction = match self {
+            Self::Fragment { direction, .. } => direction,
+            _ => {
+                let mode = *world.resource::<DirectionMode>();
+                world.resource_mut::<RunRng>().rng().sample(mode)
+            }
+        };
+        let spawned_at = SpawnedAt(**world.resource::<GameClock>());
 
         let hazard_type = match self {
-            Self::Rock => HazardType::Rock,
             Self::Ice => HazardType::Ice,
+            _ => HazardType::Rock,
+        };
+
+        let clip = match self {
+            Self::Ice => Clip::Ice,
+            _ => Clip::Rock,
+        };
+
+        let color = match self {
+            Self::Ice => world.resource::<Settings>().palette.color(Role::Ice),
+            _ => Color::WHITE,
+        };
+
+        let (size, fragments) = match self {
+            Self::LargeRock { fragments } => (120.0, fragments),
+            Self::Fragment { .. } => (55.0, 0),
+            _ => (80.0, 0),
         };
 
-        let sprite = match self {
-            Self::Rock => world
-                .get_resource::<GameAssets>()
-                .unwrap()
-                .rock_astroid
-                .clone(),
-            Self::Ice => world
-                .get_resource::<GameAssets>()
-                .unwrap()
-                .ice_astroid
-                .clone(),
+        let translation = match self {
+            Self::Fragment { position, .. } => position,
+            _ => direction.to_vec3() * -500.0 + Vec3::Z,
         };
 
-        world.spawn(AsteroidBundle {
+        let mut asteroid = world.spawn(AsteroidBundle {
             asteroid_marker: Asteroid,
             game_marker: Game,
             direction,
             hazard_type,
-            sprite: SpriteBundle {
-                texture: sprite,
-                sprite: Sprite {
-                    custom_size: Some(Vec2 { x: 80.0, y: 80.0 }),
+            spawned_at,
+            fragments: Fragments(fragments),
+            animation: Animation::new(clip),
+            sprite: world.resource::<AnimationLibrary>().sheet(
+                clip,
+                TextureAtlasSprite {
+                    color,
+                    custom_size: Some(Vec2 { x: size, y: size }),
                     ..default()
                 },
-                transform: Transform::from_translation(direction.to_vec3() * -500.0 + Vec3::Z)
-                    .with_rotation(direction.to_quat()),
-                ..default()
-            },
+                Transform::from_translation(translation).with_rotation(direction.to_quat()),
+            ),
         });
+
+        if let Self::Fragment { .. } = self {
+            asteroid.insert(Bouncing(Timer::from_seconds(0.8, TimerMode::Once)));
+        }
     }
 }
 
+/// The sides the fragments of an asteroid from `direction` come back from, starting with its neighbours.
+fn fragment_directions(direction: Direction, count: u32) -> impl Iterator<Item = Direction> {
+    [
+        direction.rotate_cw(),
+        direction.rotate_ccw(),
+        direction.rotate_cw().rotate_cw(),
+        direction,
+    ]
+    .into_iter()
+    .cycle()
+    .take(count as usize)
+}
+
 fn update_asteroids(
     mut commands: Commands,
-    assets: Res<GameAssets>,
     mut event_writer: EventWriter<HitEvent>,
     mut asteroids: Query<
         (
             Entity,
             &Direction,
             &HazardType,
+            &SpawnedAt,
+            &Fragments,
             &mut Transform,
-            &mut Handle<Image>,
+            &mut Animation,
         ),
-        With<Asteroid>,
+        (With<Asteroid>, Without<Bouncing>, Without<Impacted>),
     >,
+    speed: Res<HazardSpeed>,
     time: Res<Time>,
+    time_scale: Res<TimeScale>,
 ) {
-    for (entity, &direction, &hazard_type, mut transform, mut texture) in asteroids.iter_mut() {
-        transform.translation += direction.to_vec3() * time.delta_seconds() * 200.0;
+    for (entity, &direction, &hazard_type, spawned_at, fragments, mut transform, mut animation) in
+        asteroids.iter_mut()
+    {
+        // Large rocks are heavier and drift in a little slower.
+        let speed = if fragments.0 > 0 {
+            **speed * 0.75
+        } else {
+            **speed
+        };
+        transform.translation += direction.to_vec3() * time_scale.delta_seconds(&time) * speed;
         if transform.translation.length() <= 70.0 {
-            commands.entity(entity).despawn();
+            commands.entity(entity).insert(Impacted);
             event_writer.send(HitEvent {
                 hazard_type,
                 from_direction: direction,
+                spawned_at: spawned_at.0,
+                source: entity,
+                barrage: None,
             });
         } else if transform.translation.length() <= 100.0 && hazard_type == HazardType::Rock {
-            *texture = assets.broken_rock_astroid.clone();
+            animation.switch(Clip::BrokenRock);
+        }
+    }
+}
+
+fn update_bouncing_asteroids(
+    mut commands: Commands,
+    mut asteroids: Query<(Entity, &Direction, &mut Bouncing, &mut Transform)>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    for (entity, &direction, mut bouncing, mut transform) in asteroids.iter_mut() {
+        bouncing.0.tick(time_scale.delta(&time));
+        transform.translation = transform
+            .translation
+            .lerp(direction.to_vec3() * -FRAGMENT_RANGE + Vec3::Z, 0.1);
+        transform.rotation *= Quat::from_rotation_z(time_scale.delta_seconds(&time) * 8.0);
+
+        if bouncing.0.finished() {
+            transform.rotation = direction.to_quat();
+            commands.entity(entity).remove::<Bouncing>();
+        }
+    }
+}
+
+fn break_asteroids(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    mut outcome_events: EventReader<HitOutcomeEvent>,
+    asteroids: Query<(&Direction, &HazardType, &Fragments, &Transform), With<Asteroid>>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    for event in outcome_events.read() {
+        let Ok((&direction, &hazard_type, fragments, transform)) = asteroids.get(event.hit.source)
+        else {
+            continue;
+        };
+        commands.entity(event.hit.source).despawn_recursive();
+
+        if !event.success || hazard_type != HazardType::Rock {
+            continue;
         }
+
+        for _ in 0..DEBRIS_PIECES {
+            let angle = run_rng.rng().gen_range(-1.0..1.0);
+            let velocity = Quat::from_rotation_z(angle) * direction.to_vec3() * -300.0;
+            commands.spawn((
+                SpriteBundle {
+                    texture: assets.broken_rock_astroid.clone(),
+                    sprite: Sprite {
+                        custom_size: Some(Vec2 { x: 20.0, y: 20.0 }),
+                        ..default()
+                    },
+                    transform: *transform,
+                    ..default()
+                },
+                Debris {
+                    velocity,
+                    timer: Timer::from_seconds(0.4, TimerMode::Once),
+                },
+                Game,
+            ));
+        }
+
+        for fragment_direction in fragment_directions(direction, fragments.0) {
+            commands.add(SpawnAsteroidCommand::Fragment {
+                direction: fragment_direction,
+                position: transform.translation,
+            });
+        }
+    }
+}
+
+fn update_debris(
+    mut commands: Commands,
+    mut debris: Query<(Entity, &mut Debris, &mut Transform, &mut Sprite)>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    for (entity, mut debris, mut transform, mut sprite) in debris.iter_mut() {
+        debris.timer.tick(time_scale.delta(&time));
+        transform.translation += debris.velocity * time_scale.delta_seconds(&time);
+        transform.rotation *= Quat::from_rotation_z(time_scale.delta_seconds(&time) * 10.0);
+        sprite.color.set_a(debris.timer.percent_left());
+
+        if debris.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Drills every asteroid that reaches the spaceship, standing in for `handle_hits`.
+    fn drill_hits(
+        mut hit_events: EventReader<HitEvent>,
+        mut outcome_events: EventWriter<HitOutcomeEvent>,
+    ) {
+        for &hit in hit_events.read() {
+            outcome_events.send(HitOutcomeEvent {
+                hit,
+                tool: crate::game::layout::Tool::Drill,
+                success: true,
+                damage: 0,
+            });
+        }
+    }
+
+    #[test]
+    fn asteroids_break_in_the_frame_they_arrive() {
+        let mut world = World::new();
+        world.init_resource::<Events<HitEvent>>();
+        world.init_resource::<Events<HitOutcomeEvent>>();
+        world.init_resource::<Time>();
+        world.insert_resource(GameAssets::placeholder());
+        world.insert_resource(RunRng::new(0));
+        world.insert_resource(HazardSpeed(200.0));
+        world.insert_resource(TimeScale(1.0));
+        let asteroid = world
+            .spawn((
+                Asteroid,
+                Direction::Up,
+                HazardType::Rock,
+                SpawnedAt(0.0),
+                Fragments(0),
+                Transform::from_xyz(0.0, -60.0, 1.0),
+                Animation::new(Clip::Rock),
+            ))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems((update_asteroids, drill_hits, break_asteroids).chain());
+        schedule.run(&mut world);
+
+        assert!(world.get_entity(asteroid).is_none());
+        assert_eq!(world.query::<&Debris>().iter(&world).count(), DEBRIS_PIECES);
     }
 }
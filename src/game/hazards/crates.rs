@@ -3,26 +3,50 @@ use bevy::prelude::*;
 
 use rand::prelude::*;
 
-use crate::{game::Game, utils::Direction, AppState, GameAssets};
+use crate::{
+    game::{
+        effects::{ActiveEffects, PowerUp},
+        progression::Progress,
+        running,
+        spaceship::{handle_hits, HitOutcomeEvent},
+        Game,
+    },
+    settings::Settings,
+    utils::{Direction, DirectionMode},
+    GameAssets,
+};
 
-use super::{HazardType, HitEvent};
+use super::{GameClock, HazardSpeed, HazardType, HitEvent, RunRng, SpawnedAt, TimeScale};
 
 pub struct CratePlugin;
 
 impl Plugin for CratePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, update_crates.run_if(in_state(AppState::Playing)));
+        app.add_systems(
+            Update,
+            (
+                update_crates.before(handle_hits),
+                open_crates.after(handle_hits),
+            )
+                .run_if(running),
+        );
     }
 }
 
 #[derive(Component)]
 struct Crate;
 
+/// A crate that has reached the spaceship and is waiting to find out if it was collected.
+#[derive(Component)]
+struct Impacted;
+
 #[derive(Bundle)]
 struct CrateBundle {
     crate_marker: Crate,
     game_marker: Game,
     direction: Direction,
+    hazard_type: HazardType,
+    spawned_at: SpawnedAt,
     #[bundle()]
     sprite: SpriteBundle,
 }
@@ -31,13 +55,20 @@ pub struct SpawnCrateCommand;
 
 impl Command for SpawnCrateCommand {
     fn apply(self, world: &mut World) {
-        let mut rng = thread_rng();
-        let direction: Direction = rng.gen();
+        let mode = *world.resource::<DirectionMode>();
+        let mut run_rng = world.resource_mut::<RunRng>();
+        let rng = run_rng.rng();
+        let direction = rng.sample(mode);
+        let power_up: Option<PowerUp> = rng.gen_bool(0.4).then(|| rng.gen());
+        let spawned_at = SpawnedAt(**world.resource::<GameClock>());
+        let palette = world.resource::<Settings>().palette;
 
-        world.spawn(CrateBundle {
+        let mut health_crate = world.spawn(CrateBundle {
             crate_marker: Crate,
             game_marker: Game,
             direction,
+            hazard_type: HazardType::Crate,
+            spawned_at,
             sprite: SpriteBundle {
                 texture: world
                     .get_resource::<GameAssets>()
@@ -45,6 +76,7 @@ impl Command for SpawnCrateCommand {
                     .health_crate
                     .clone(),
                 sprite: Sprite {
+                    color: power_up.map_or(Color::WHITE, |power_up| power_up.color(palette)),
                     custom_size: Some(Vec2 { x: 50.0, y: 50.0 }),
                     ..default()
                 },
@@ -52,25 +84,104 @@ impl Command for SpawnCrateCommand {
                 ..default()
             },
         });
+
+        if let Some(power_up) = power_up {
+            health_crate.insert(power_up);
+        }
     }
 }
 
 fn update_crates(
     mut commands: Commands,
     mut event_writer: EventWriter<HitEvent>,
-    mut crates: Query<(Entity, &Direction, &mut Transform), With<Crate>>,
+    mut crates: Query<
+        (Entity, &Direction, &SpawnedAt, &mut Transform),
+        (With<Crate>, Without<Impacted>),
+    >,
+    speed: Res<HazardSpeed>,
     time: Res<Time>,
+    time_scale: Res<TimeScale>,
 ) {
-    for (entity, &direction, mut transform) in crates.iter_mut() {
-        transform.translation += direction.to_vec3() * time.delta_seconds() * 200.0;
-        transform.rotation *= Quat::from_rotation_z(time.delta_seconds() * 2.0);
+    for (entity, &direction, spawned_at, mut transform) in crates.iter_mut() {
+        transform.translation += direction.to_vec3() * time_scale.delta_seconds(&time) * **speed;
+        transform.rotation *= Quat::from_rotation_z(time_scale.delta_seconds(&time) * 2.0);
 
         if transform.translation.length() <= 70.0 {
-            commands.entity(entity).despawn();
+            commands.entity(entity).insert(Impacted);
             event_writer.send(HitEvent {
                 hazard_type: HazardType::Crate,
                 from_direction: direction,
+                spawned_at: spawned_at.0,
+                source: entity,
+                barrage: None,
             });
         }
     }
 }
+
+fn open_crates(
+    mut commands: Commands,
+    mut outcome_events: EventReader<HitOutcomeEvent>,
+    crates: Query<Option<&PowerUp>, With<Crate>>,
+    mut effects: ResMut<ActiveEffects>,
+    progress: Res<Progress>,
+) {
+    for event in outcome_events.read() {
+        let Ok(power_up) = crates.get(event.hit.source) else {
+            continue;
+        };
+        commands.entity(event.hit.source).despawn_recursive();
+
+        if let (true, Some(&power_up)) = (event.success, power_up) {
+            effects.activate(power_up, progress.power_up_duration_scale());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Collects every crate that reaches the spaceship, standing in for `handle_hits`.
+    fn collect_hits(
+        mut hit_events: EventReader<HitEvent>,
+        mut outcome_events: EventWriter<HitOutcomeEvent>,
+    ) {
+        for &hit in hit_events.read() {
+            outcome_events.send(HitOutcomeEvent {
+                hit,
+                tool: crate::game::layout::Tool::Cockpit,
+                success: true,
+                damage: 0,
+            });
+        }
+    }
+
+    #[test]
+    fn crates_are_opened_in_the_frame_they_arrive() {
+        let mut world = World::new();
+        world.init_resource::<Events<HitEvent>>();
+        world.init_resource::<Events<HitOutcomeEvent>>();
+        world.init_resource::<Time>();
+        world.init_resource::<ActiveEffects>();
+        world.init_resource::<Progress>();
+        world.insert_resource(HazardSpeed(200.0));
+        world.insert_resource(TimeScale(1.0));
+        let health_crate = world
+            .spawn((
+                Crate,
+                Direction::Up,
+                SpawnedAt(0.0),
+                Transform::from_xyz(0.0, -60.0, 1.0),
+                PowerUp::DoubleScore,
+            ))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems((update_crates, collect_hits, open_crates).chain());
+        schedule.run(&mut world);
+
+        assert!(world.get_entity(health_crate).is_none());
+        assert!(world.resource::<ActiveEffects>().has(PowerUp::DoubleScore));
+    }
+}
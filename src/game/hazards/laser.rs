@@ -5,26 +5,32 @@ use bevy::prelude::*;
 use rand::prelude::*;
 
 use crate::{
-    game::{Game, Shaking},
-    utils::Direction,
-    AppState, GameAssets,
+    animation::{Animation, AnimationLibrary, Clip},
+    game::{
+        difficulty::Difficulty, layout::Tool, running, spaceship::HitOutcomeEvent, Game, Shaking,
+    },
+    settings::Settings,
+    utils::{Direction, DirectionMode},
 };
 
-use super::{HazardType, HitEvent};
+use super::{GameClock, HazardType, HitEvent, RunRng, SpawnedAt, TimeScale};
 
 pub struct LaserPlugin;
 
 impl Plugin for LaserPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, update_satilites.run_if(in_state(AppState::Playing)));
+        app.add_systems(
+            Update,
+            (update_satilites, destroy_reflected_satilites).run_if(running),
+        );
     }
 }
 
 #[derive(Component)]
 struct Satilite;
 
-#[derive(Component)]
-enum SatiliteState {
+#[derive(Component, PartialEq, Eq)]
+pub enum SatiliteState {
     Idle,
     Charging,
     Firing,
@@ -34,81 +40,149 @@ enum SatiliteState {
 #[derive(Component)]
 struct SatiliteTimer(Timer);
 
+/// The beam a satilite fires, which is removed when it stops firing.
+#[derive(Component)]
+struct LaserBeam;
+
+/// Every satilite belongs to a group which charges and fires all of its satilites at once.
 #[derive(Bundle)]
-struct SatiliteBundle {
-    satilite_marker: Satilite,
+struct SatiliteGroupBundle {
     game_marker: Game,
-    direction: Direction,
     satilite_state: SatiliteState,
     timer: SatiliteTimer,
     #[bundle()]
-    sprite: SpriteBundle,
+    spatial: SpatialBundle,
+}
+
+#[derive(Bundle)]
+struct SatiliteBundle {
+    satilite_marker: Satilite,
+    direction: Direction,
+    hazard_type: HazardType,
+    spawned_at: SpawnedAt,
+    animation: Animation,
+    #[bundle()]
+    sprite: SpriteSheetBundle,
 }
 
-pub struct SpawnLaserCommand;
+/// Spawns a group of satilites, each from a different direction.
+///
+/// A group of more than one is a barrage.
+pub struct SpawnLaserCommand {
+    pub satilites: usize,
+}
 
 impl Command for SpawnLaserCommand {
     fn apply(self, world: &mut World) {
-        let mut rng = thread_rng();
-        let direction: Direction = rng.gen();
-
-        world.spawn(SatiliteBundle {
-            satilite_marker: Satilite,
-            game_marker: Game,
-            direction,
-            satilite_state: SatiliteState::Idle,
-            timer: SatiliteTimer(Timer::from_seconds(1.5, TimerMode::Once)),
-            sprite: SpriteBundle {
-                texture: world
-                    .get_resource::<GameAssets>()
-                    .unwrap()
-                    .satilite_idle
-                    .clone(),
-                sprite: Sprite {
-                    custom_size: Some(Vec2 { x: 120.0, y: 120.0 }),
-                    ..default()
-                },
-                transform: Transform::from_translation(
-                    direction.to_vec3() * -500.0 + Vec3::Z * 2.0,
-                )
-                .with_rotation(direction.to_quat()),
+        let mode = *world.resource::<DirectionMode>();
+        let directions = mode
+            .directions()
+            .choose_multiple(world.resource_mut::<RunRng>().rng(), self.satilites);
+        let spawned_at = SpawnedAt(**world.resource::<GameClock>());
+        let sprite = world.resource::<AnimationLibrary>().sheet(
+            Clip::SatelliteIdle,
+            TextureAtlasSprite {
+                custom_size: Some(Vec2 { x: 120.0, y: 120.0 }),
                 ..default()
             },
-        });
+            Transform::IDENTITY,
+        );
+
+        world
+            .spawn(SatiliteGroupBundle {
+                game_marker: Game,
+                satilite_state: SatiliteState::Idle,
+                timer: SatiliteTimer(Timer::from_seconds(1.5, TimerMode::Once)),
+                spatial: SpatialBundle::default(),
+            })
+            .with_children(|parent| {
+                for &direction in directions {
+                    parent.spawn(SatiliteBundle {
+                        satilite_marker: Satilite,
+                        direction,
+                        hazard_type: HazardType::Laser,
+                        spawned_at,
+                        animation: Animation::new(Clip::SatelliteIdle),
+                        sprite: SpriteSheetBundle {
+                            transform: Transform::from_translation(
+                                direction.to_vec3() * -500.0 + Vec3::Z * 2.0,
+                            )
+                            .with_rotation(direction.to_quat()),
+                            ..sprite.clone()
+                        },
+                    });
+                }
+            });
     }
 }
 
 fn update_satilites(
     mut commands: Commands,
     time: Res<Time>,
-    mut query: Query<(
+    time_scale: Res<TimeScale>,
+    mut groups: Query<(
+        Entity,
         &mut SatiliteTimer,
         &mut SatiliteState,
-        &mut Handle<Image>,
-        &mut Transform,
-        &Direction,
-        Entity,
+        Option<&Children>,
     )>,
-    assets: Res<GameAssets>,
+    mut satilites: Query<
+        (
+            Entity,
+            &mut Animation,
+            &mut Transform,
+            &Direction,
+            &SpawnedAt,
+        ),
+        With<Satilite>,
+    >,
+    beams: Query<(Entity, &Parent), With<LaserBeam>>,
+    library: Res<AnimationLibrary>,
+    difficulty: Res<Difficulty>,
+    settings: Res<Settings>,
     mut hit_event_writer: EventWriter<HitEvent>,
 ) {
-    for (mut timer, mut state, mut sprite, mut transform, &direction, entity) in query.iter_mut() {
-        timer.0.tick(time.delta());
+    // A dimmer beam flashes less brightly when it fires.
+    let beam_color = if settings.photosensitivity_safe {
+        Color::rgba(1.0, 1.0, 1.0, 0.5)
+    } else {
+        Color::WHITE
+    };
+    for (group, mut timer, mut state, children) in groups.iter_mut() {
+        // Every satilite in the group has been destroyed by a reflector.
+        let Some(children) = children else {
+            commands.entity(group).despawn();
+            continue;
+        };
+
+        timer.0.tick(time_scale.delta(&time));
+        let barrage = (children.len() > 1).then_some(group);
+        let mut satilites = satilites.iter_many_mut(children);
 
         match *state {
             SatiliteState::Idle => {
-                transform.translation = transform
-                    .translation
-                    .lerp(direction.to_vec3() * -320.0 + Vec3::Z, 0.1);
-
-                if timer.0.finished() {
+                let finished = timer.0.finished();
+                if finished {
                     *state = SatiliteState::Charging;
-                    *sprite = assets.satilite_charging.clone();
-                    timer.0.set_duration(Duration::from_secs_f32(0.5));
+                    timer.0.set_duration(Duration::from_secs_f32(
+                        difficulty.preset().laser_charge_time,
+                    ));
                     timer.0.reset();
-                    commands
-                        .entity(entity)
-                        .insert(Shaking(Timer::from_seconds(1.0, TimerMode::Once)));
+                }
+
+                while let Some((entity, mut animation, mut transform, &direction, _)) =
+                    satilites.fetch_next()
+                {
+                    transform.translation = transform
+                        .translation
+                        .lerp(direction.to_vec3() * -320.0 + Vec3::Z, 0.1);
+
+                    if finished {
+                        animation.play(Clip::SatelliteCharge);
+                        commands
+                            .entity(entity)
+                            .insert(Shaking(Timer::from_seconds(1.0, TimerMode::Once)));
+                    }
                 }
             }
             SatiliteState::Charging => {
@@ -117,42 +191,73 @@ fn update_satilites(
                     timer.0.set_duration(Duration::from_secs_f32(0.5));
                     timer.0.reset();
 
-                    commands.entity(entity).with_children(|parent| {
-                        parent.spawn(SpriteBundle {
-                            texture: assets.laser.clone(),
-                            sprite: Sprite {
-                                custom_size: Some(Vec2 { x: 20.0, y: 300.0 }),
-                                ..default()
-                            },
-                            transform: Transform::from_translation(Vec3::new(0.0, 200.0, -1.0)),
-                            ..default()
+                    while let Some((entity, _, _, &direction, spawned_at)) = satilites.fetch_next()
+                    {
+                        commands.entity(entity).with_children(|parent| {
+                            parent.spawn((
+                                library.sheet(
+                                    Clip::LaserBeam,
+                                    TextureAtlasSprite {
+                                        color: beam_color,
+                                        custom_size: Some(Vec2 { x: 20.0, y: 300.0 }),
+                                        ..default()
+                                    },
+                                    Transform::from_translation(Vec3::new(0.0, 200.0, -1.0)),
+                                ),
+                                Animation::new(Clip::LaserBeam),
+                                LaserBeam,
+                            ));
                         });
-                    });
 
-                    hit_event_writer.send(HitEvent {
-                        from_direction: direction,
-                        hazard_type: HazardType::Laser,
-                    });
+                        hit_event_writer.send(HitEvent {
+                            from_direction: direction,
+                            hazard_type: HazardType::Laser,
+                            spawned_at: spawned_at.0,
+                            source: entity,
+                            barrage,
+                        });
+                    }
                 }
             }
             SatiliteState::Firing => {
                 if timer.0.finished() {
                     *state = SatiliteState::Retreating;
-                    *sprite = assets.satilite_idle.clone();
                     timer.0.set_duration(Duration::from_secs_f32(1.0));
                     timer.0.reset();
-                    commands.entity(entity).despawn_descendants();
+
+                    while let Some((entity, mut animation, ..)) = satilites.fetch_next() {
+                        animation.switch(Clip::SatelliteIdle);
+                        for (beam, parent) in beams.iter() {
+                            if parent.get() == entity {
+                                commands.entity(beam).despawn_recursive();
+                            }
+                        }
+                    }
                 }
             }
             SatiliteState::Retreating => {
-                transform.translation = transform
-                    .translation
-                    .lerp(direction.to_vec3() * -500.0 + Vec3::Z, 0.1);
+                while let Some((_, _, mut transform, &direction, _)) = satilites.fetch_next() {
+                    transform.translation = transform
+                        .translation
+                        .lerp(direction.to_vec3() * -500.0 + Vec3::Z, 0.1);
+                }
 
                 if timer.0.finished() {
-                    commands.entity(entity).despawn();
+                    commands.entity(group).despawn_recursive();
                 }
             }
         }
     }
 }
+
+fn destroy_reflected_satilites(
+    mut commands: Commands,
+    mut outcome_events: EventReader<HitOutcomeEvent>,
+    satilites: Query<(), With<Satilite>>,
+) {
+    for event in outcome_events.read() {
+        if event.success && event.tool == Tool::Reflector && satilites.contains(event.hit.source) {
+            commands.entity(event.hit.source).despawn_recursive();
+        }
+    }
+}
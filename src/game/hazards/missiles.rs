@@ -0,0 +1,179 @@
+use bevy::ecs::system::Command;
+use bevy::prelude::*;
+
+use rand::prelude::*;
+
+use crate::{
+    game::{difficulty::Difficulty, layout::Tool, running, Game},
+    palette::Role,
+    settings::Settings,
+    utils::{Direction, DirectionMode},
+    AppState, GameAssets,
+};
+
+use super::{GameClock, HazardSpeed, HazardType, HitEvent, RunRng, SpawnedAt, TimeScale};
+
+const SWITCH_DISTANCE: f32 = 300.0;
+const SWITCH_DURATION: f32 = 0.6;
+const BLINK_RATE: f32 = 10.0;
+
+pub struct MissilePlugin;
+
+impl Plugin for MissilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MissileConfig>()
+            .add_systems(OnEnter(AppState::Playing), configure_missiles)
+            .add_systems(Update, update_missiles.run_if(running));
+    }
+}
+
+/// Which side of the spaceship has to meet a missile once it has finished switching sides.
+#[derive(Resource)]
+pub struct MissileConfig {
+    pub countered_by: Tool,
+}
+
+impl Default for MissileConfig {
+    fn default() -> Self {
+        Self {
+            countered_by: Tool::Shield,
+        }
+    }
+}
+
+/// Takes the countering tool from the preset chosen for this run.
+fn configure_missiles(mut config: ResMut<MissileConfig>, difficulty: Res<Difficulty>) {
+    config.countered_by = difficulty.preset().missile_counter;
+}
+
+#[derive(Component)]
+enum Missile {
+    Incoming,
+    Switching {
+        from: Direction,
+        to: Direction,
+        timer: Timer,
+    },
+    Homing,
+}
+
+#[derive(Bundle)]
+struct MissileBundle {
+    missile: Missile,
+    game_marker: Game,
+    direction: Direction,
+    hazard_type: HazardType,
+    spawned_at: SpawnedAt,
+    #[bundle()]
+    sprite: SpriteBundle,
+}
+
+pub struct SpawnMissileCommand;
+
+impl Command for SpawnMissileCommand {
+    fn apply(self, world: &mut World) {
+        let mode = *world.resource::<DirectionMode>();
+        let direction = world.resource_mut::<RunRng>().rng().sample(mode);
+        let spawned_at = SpawnedAt(**world.resource::<GameClock>());
+        let palette = world.resource::<Settings>().palette;
+
+        world.spawn(MissileBundle {
+            missile: Missile::Incoming,
+            game_marker: Game,
+            direction,
+            hazard_type: HazardType::Missile,
+            spawned_at,
+            sprite: SpriteBundle {
+                texture: world.get_resource::<GameAssets>().unwrap().laser.clone(),
+                sprite: Sprite {
+                    color: palette.color(Role::Warning),
+                    custom_size: Some(Vec2 { x: 20.0, y: 60.0 }),
+                    ..default()
+                },
+                transform: Transform::from_translation(direction.to_vec3() * -500.0 + Vec3::Z)
+                    .with_rotation(direction.to_quat()),
+                ..default()
+            },
+        });
+    }
+}
+
+fn update_missiles(
+    mut commands: Commands,
+    mut event_writer: EventWriter<HitEvent>,
+    mut missiles: Query<(
+        Entity,
+        &mut Missile,
+        &mut Direction,
+        &SpawnedAt,
+        &mut Transform,
+        &mut Sprite,
+    )>,
+    speed: Res<HazardSpeed>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    settings: Res<Settings>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    let danger = settings.palette.color(Role::Danger);
+    for (entity, mut missile, mut direction, spawned_at, mut transform, mut sprite) in
+        missiles.iter_mut()
+    {
+        match *missile {
+            Missile::Incoming => {
+                transform.translation +=
+                    direction.to_vec3() * time_scale.delta_seconds(&time) * **speed;
+                if transform.translation.length() <= SWITCH_DISTANCE {
+                    let to = if run_rng.rng().gen() {
+                        direction.rotate_cw()
+                    } else {
+                        direction.rotate_ccw()
+                    };
+                    *missile = Missile::Switching {
+                        from: *direction,
+                        to,
+                        timer: Timer::from_seconds(SWITCH_DURATION, TimerMode::Once),
+                    };
+                }
+            }
+            Missile::Switching {
+                from,
+                to,
+                ref mut timer,
+            } => {
+                timer.tick(time_scale.delta(&time));
+
+                // Swing around the spaceship to the new side, blinking red as a warning.
+                let rotation = from.to_quat().slerp(to.to_quat(), timer.percent());
+                let radius = transform.translation.truncate().length();
+                transform.translation = rotation * Vec3::NEG_Y * radius + Vec3::Z;
+                transform.rotation = rotation;
+                sprite.color = if settings.blink_off(timer.elapsed_secs(), BLINK_RATE) {
+                    Color::WHITE
+                } else {
+                    danger
+                };
+
+                if timer.finished() {
+                    *direction = to;
+                    *missile = Missile::Homing;
+                    sprite.color = danger;
+                }
+            }
+            Missile::Homing => {
+                transform.translation +=
+                    direction.to_vec3() * time_scale.delta_seconds(&time) * **speed;
+                if transform.translation.length() <= 70.0 {
+                    commands.entity(entity).despawn_recursive();
+                    event_writer.send(HitEvent {
+                        hazard_type: HazardType::Missile,
+                        from_direction: *direction,
+                        spawned_at: spawned_at.0,
+                        source: entity,
+                        barrage: None,
+                    });
+                }
+            }
+        }
+    }
+}
@@ -1,43 +1,172 @@
 use std::time::Duration;
 
 use bevy::prelude::*;
-use rand::distributions::Standard;
-use rand::prelude::*;
+use rand::{prelude::*, rngs::StdRng};
+use serde::{Deserialize, Serialize};
 
-use crate::{utils::Direction, AppState};
+use crate::{settings::Settings, utils::Direction, AppState, GameAssets};
 
-use super::score::Score;
+use super::{difficulty::Difficulty, effects::ActiveEffects, running, score::Score};
+use adaptive::AdaptiveDifficulty;
 
+pub mod adaptive;
 mod asteroids;
 mod crates;
 mod laser;
+mod missiles;
+mod scramblers;
 
+pub use laser::SatiliteState;
+pub use missiles::MissileConfig;
+
+/// Counts down to the next hazard, its duration shrinks as the score goes up.
+#[derive(Resource, Deref, DerefMut)]
+pub struct HazardTimer(Timer);
+
+/// How fast asteroids and crates travel towards the spaceship, in pixels per second.
+#[derive(Resource, Deref, DerefMut)]
+pub struct HazardSpeed(pub f32);
+
+/// How fast time passes for hazards, below 1 while the slow mode assist is on.
 #[derive(Resource, Deref, DerefMut)]
-struct HazardTimer(Timer);
+pub struct TimeScale(pub f32);
+
+impl TimeScale {
+    pub fn delta(&self, time: &Time) -> Duration {
+        time.delta().mul_f32(self.0)
+    }
+
+    pub fn delta_seconds(&self, time: &Time) -> f32 {
+        time.delta_seconds() * self.0
+    }
+}
 
-#[derive(Component, PartialEq, Eq, Clone, Copy)]
+/// Seconds of hazard time since the run started, which passes slower while the slow mode assist is on.
+#[derive(Resource, Default, Deref)]
+pub struct GameClock(f32);
+
+/// Makes every random choice that shapes a run, so it can be recreated from its seed.
+#[derive(Resource)]
+pub struct RunRng {
+    pub seed: u64,
+    rng: StdRng,
+}
+
+impl RunRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+}
+
+#[derive(Component, PartialEq, Eq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum HazardType {
     Rock,
     Ice,
     Laser,
     Crate,
+    Missile,
+    Scrambler,
 }
 
-impl Distribution<HazardType> for Standard {
+impl HazardType {
+    pub const ALL: [Self; 6] = [
+        Self::Rock,
+        Self::Ice,
+        Self::Laser,
+        Self::Crate,
+        Self::Missile,
+        Self::Scrambler,
+    ];
+
+    /// The key of its name in the translation files.
+    pub const fn name_key(self) -> &'static str {
+        match self {
+            Self::Rock => "hazard-rock",
+            Self::Ice => "hazard-ice",
+            Self::Laser => "hazard-laser",
+            Self::Crate => "hazard-crate",
+            Self::Missile => "hazard-missile",
+            Self::Scrambler => "hazard-scrambler",
+        }
+    }
+
+    /// A symbol for telling hazards apart without relying on colour.
+    pub const fn glyph(self) -> &'static str {
+        match self {
+            Self::Rock => "#",
+            Self::Ice => "*",
+            Self::Laser => "!",
+            Self::Crate => "+",
+            Self::Missile => "^",
+            Self::Scrambler => "?",
+        }
+    }
+}
+
+/// Relative spawn weights for each hazard type, in the order of [`HazardType::ALL`].
+#[derive(Clone, Copy, Debug)]
+pub struct HazardMix(pub [u32; 6]);
+
+impl Default for HazardMix {
+    fn default() -> Self {
+        Self([4, 3, 2, 1, 1, 1])
+    }
+}
+
+impl HazardMix {
+    /// Removes every hazard type that isn't in `hazards`.
+    pub fn only(mut self, hazards: &[HazardType]) -> Self {
+        for (hazard_type, weight) in HazardType::ALL.iter().zip(&mut self.0) {
+            if !hazards.contains(hazard_type) {
+                *weight = 0;
+            }
+        }
+        self
+    }
+}
+
+impl Distribution<HazardType> for HazardMix {
+    /// Falls back to rocks, which every preset has, when filtering has left nothing to pick from.
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> HazardType {
-        match rng.gen_range(0..10) {
-            0..=3 => HazardType::Rock,  // 4/10 chance
-            4..=6 => HazardType::Ice,   // 3/10 chance
-            7..=8 => HazardType::Laser, // 2/10 chance
-            _ => HazardType::Crate,     // 1/10 chance
+        let total = self.0.iter().sum::<u32>();
+        if total == 0 {
+            return HazardType::Rock;
         }
+        let mut roll = rng.gen_range(0..total);
+        for (hazard_type, &weight) in HazardType::ALL.iter().zip(&self.0) {
+            if roll < weight {
+                return *hazard_type;
+            }
+            roll -= weight;
+        }
+        unreachable!()
     }
 }
 
-#[derive(Event)]
+/// The backing for a hazard's glyph, which stays upright however the hazard is turned.
+#[derive(Component)]
+struct GlyphBadge;
+
+/// The [`GameClock`] time at which a hazard was spawned, used to measure how long the player took to react.
+#[derive(Component, Clone, Copy)]
+pub struct SpawnedAt(pub f32);
+
+#[derive(Event, Clone, Copy)]
 pub struct HitEvent {
     pub hazard_type: HazardType,
     pub from_direction: Direction,
+    pub spawned_at: f32,
+    /// The hazard entity, which may already have been despawned.
+    pub source: Entity,
+    /// Shared by every beam of a barrage, which are all fired in the same frame.
+    pub barrage: Option<Entity>,
 }
 
 pub struct HazardsPlugin;
@@ -45,37 +174,205 @@ pub struct HazardsPlugin;
 impl Plugin for HazardsPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(HazardTimer(Timer::from_seconds(1.0, TimerMode::Repeating)))
+            .insert_resource(HazardSpeed(Difficulty::default().preset().hazard_speed))
+            .insert_resource(TimeScale(1.0))
+            .init_resource::<GameClock>()
+            .insert_resource(RunRng::new(0))
             .add_event::<HitEvent>()
-            .add_systems(Update, spawn_hazards.run_if(in_state(AppState::Playing)))
+            .add_systems(
+                OnEnter(AppState::Playing),
+                (seed_run, reset_hazard_timer, reset_game_clock),
+            )
+            .add_systems(
+                Update,
+                (
+                    tick_game_clock,
+                    update_hazard_speed,
+                    spawn_hazards,
+                    add_glyph_badges,
+                    keep_glyphs_upright,
+                )
+                    .run_if(running),
+            )
+            .add_systems(
+                Update,
+                update_time_scale.run_if(resource_changed::<Settings>()),
+            )
             .add_plugins((
+                adaptive::AdaptivePlugin,
                 asteroids::AsteroidsPlugin,
                 laser::LaserPlugin,
                 crates::CratePlugin,
+                missiles::MissilePlugin,
+                scramblers::ScramblerPlugin,
             ));
     }
 }
 
+fn seed_run(mut run_rng: ResMut<RunRng>) {
+    *run_rng = RunRng::new(thread_rng().gen());
+}
+
+/// Starts every run waiting the preset's interval for the first hazard, whatever the last run left behind.
+fn reset_hazard_timer(mut timer: ResMut<HazardTimer>, difficulty: Res<Difficulty>) {
+    **timer = Timer::from_seconds(difficulty.preset().base_interval, TimerMode::Repeating);
+}
+
+fn reset_game_clock(mut clock: ResMut<GameClock>) {
+    *clock = GameClock::default();
+}
+
+fn tick_game_clock(mut clock: ResMut<GameClock>, time: Res<Time>, time_scale: Res<TimeScale>) {
+    clock.0 += time_scale.delta_seconds(&time);
+}
+
+fn update_hazard_speed(
+    mut speed: ResMut<HazardSpeed>,
+    difficulty: Res<Difficulty>,
+    adaptive: Res<AdaptiveDifficulty>,
+    effects: Res<ActiveEffects>,
+) {
+    **speed = difficulty.preset().hazard_speed * adaptive.speed_scale() * effects.speed_scale();
+}
+
+fn update_time_scale(mut time_scale: ResMut<TimeScale>, settings: Res<Settings>) {
+    **time_scale = settings.game_speed;
+}
+
 fn spawn_hazards(
     mut commands: Commands,
     time: Res<Time>,
+    time_scale: Res<TimeScale>,
     mut timer: ResMut<HazardTimer>,
     score: Res<Score>,
+    difficulty: Res<Difficulty>,
+    adaptive: Res<AdaptiveDifficulty>,
+    mut run_rng: ResMut<RunRng>,
 ) {
-    timer.tick(time.delta());
+    timer.tick(time_scale.delta(&time));
     if !timer.just_finished() {
         return;
     }
+    let preset = difficulty.preset();
     timer.set_duration(Duration::from_secs_f32(
-        15.0 / (score.score as f32 + 10.0) + 0.5,
+        ((preset.base_interval - 0.5) * 10.0 / (score.score as f32 + 10.0) + 0.5)
+            * adaptive.interval_scale(),
     ));
 
-    let mut rng = thread_rng();
-    let hazard_type: HazardType = rng.gen();
+    let rng = run_rng.rng();
+    let hazard_type = rng.sample(adaptive.hazard_mix().only(preset.hazards));
 
     match hazard_type {
+        HazardType::Rock if rng.gen_ratio(1, 5) => {
+            commands.add(asteroids::SpawnAsteroidCommand::LargeRock { fragments: 2 });
+        }
         HazardType::Rock => commands.add(asteroids::SpawnAsteroidCommand::Rock),
         HazardType::Ice => commands.add(asteroids::SpawnAsteroidCommand::Ice),
-        HazardType::Laser => commands.add(laser::SpawnLaserCommand),
+        HazardType::Laser if score.score >= 20 && rng.gen_ratio(1, 4) => {
+            commands.add(laser::SpawnLaserCommand {
+                satilites: rng.gen_range(2..=3),
+            });
+        }
+        HazardType::Laser => commands.add(laser::SpawnLaserCommand { satilites: 1 }),
         HazardType::Crate => commands.add(crates::SpawnCrateCommand),
+        HazardType::Missile => commands.add(missiles::SpawnMissileCommand),
+        HazardType::Scrambler => commands.add(scramblers::SpawnScramblerCommand),
     };
 }
+
+fn add_glyph_badges(
+    mut commands: Commands,
+    hazards: Query<(Entity, &HazardType), Added<HazardType>>,
+    settings: Res<Settings>,
+    assets: Res<GameAssets>,
+) {
+    if !settings.shape_cues {
+        return;
+    }
+    for (entity, hazard_type) in hazards.iter() {
+        commands.entity(entity).with_children(|parent| {
+            parent
+                .spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::rgba(0.0, 0.0, 0.0, 0.6),
+                            custom_size: Some(Vec2::splat(30.0)),
+                            ..default()
+                        },
+                        transform: Transform::from_translation(Vec3::Z),
+                        ..default()
+                    },
+                    GlyphBadge,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(Text2dBundle {
+                        text: Text::from_section(
+                            hazard_type.glyph(),
+                            TextStyle {
+                                font: assets.font.clone(),
+                                font_size: 28.0,
+                                color: Color::WHITE,
+                            },
+                        ),
+                        transform: Transform::from_translation(Vec3::Z),
+                        ..default()
+                    });
+                });
+        });
+    }
+}
+
+fn keep_glyphs_upright(
+    mut badges: Query<(&Parent, &mut Transform), With<GlyphBadge>>,
+    hazards: Query<&Transform, Without<GlyphBadge>>,
+) {
+    for (parent, mut transform) in badges.iter_mut() {
+        if let Ok(hazard) = hazards.get(parent.get()) {
+            transform.rotation = hazard.rotation.inverse();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mix_only_samples_weighted_types() {
+        let mix = HazardMix([0, 3, 0, 1, 0, 0]);
+        let mut rng = StdRng::seed_from_u64(0);
+        let samples: Vec<HazardType> = (0..1000).map(|_| mix.sample(&mut rng)).collect();
+
+        assert!(samples
+            .iter()
+            .all(|&hazard_type| matches!(hazard_type, HazardType::Ice | HazardType::Crate)));
+        let ice = samples
+            .iter()
+            .filter(|&&hazard_type| hazard_type == HazardType::Ice)
+            .count();
+        assert!((650..850).contains(&ice), "{ice} of 1000 were ice");
+    }
+
+    #[test]
+    fn mix_with_one_weight_always_samples_it() {
+        let mix = HazardMix([0, 0, 0, 0, 0, 7]);
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..100 {
+            assert_eq!(mix.sample(&mut rng), HazardType::Scrambler);
+        }
+    }
+
+    #[test]
+    fn only_zeroes_missing_types() {
+        let mix = HazardMix::default().only(&[HazardType::Rock, HazardType::Laser]);
+        assert_eq!(mix.0, [4, 0, 2, 0, 0, 0]);
+    }
+
+    #[test]
+    fn mix_without_weights_falls_back_to_rocks() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(HazardMix([0; 6]).sample(&mut rng), HazardType::Rock);
+        let filtered = HazardMix([0, 0, 0, 1, 1, 1]).only(&[HazardType::Rock, HazardType::Ice]);
+        assert_eq!(filtered.sample(&mut rng), HazardType::Rock);
+    }
+}
@@ -0,0 +1,94 @@
+use bevy::ecs::system::Command;
+use bevy::prelude::*;
+
+use rand::prelude::*;
+
+use crate::{
+    game::{running, Game},
+    palette::Role,
+    settings::Settings,
+    utils::{Direction, DirectionMode},
+    GameAssets,
+};
+
+use super::{GameClock, HazardSpeed, HazardType, HitEvent, RunRng, SpawnedAt, TimeScale};
+
+pub struct ScramblerPlugin;
+
+impl Plugin for ScramblerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_scramblers.run_if(running));
+    }
+}
+
+#[derive(Component)]
+struct Scrambler;
+
+#[derive(Bundle)]
+struct ScramblerBundle {
+    scrambler_marker: Scrambler,
+    game_marker: Game,
+    direction: Direction,
+    hazard_type: HazardType,
+    spawned_at: SpawnedAt,
+    #[bundle()]
+    sprite: SpriteBundle,
+}
+
+pub struct SpawnScramblerCommand;
+
+impl Command for SpawnScramblerCommand {
+    fn apply(self, world: &mut World) {
+        let mode = *world.resource::<DirectionMode>();
+        let direction = world.resource_mut::<RunRng>().rng().sample(mode);
+        let spawned_at = SpawnedAt(**world.resource::<GameClock>());
+        let palette = world.resource::<Settings>().palette;
+
+        world.spawn(ScramblerBundle {
+            scrambler_marker: Scrambler,
+            game_marker: Game,
+            direction,
+            hazard_type: HazardType::Scrambler,
+            spawned_at,
+            sprite: SpriteBundle {
+                texture: world
+                    .get_resource::<GameAssets>()
+                    .unwrap()
+                    .satilite_charging
+                    .clone(),
+                sprite: Sprite {
+                    color: palette.color(Role::Scrambled),
+                    custom_size: Some(Vec2 { x: 60.0, y: 60.0 }),
+                    ..default()
+                },
+                transform: Transform::from_translation(direction.to_vec3() * -500.0 + Vec3::Z),
+                ..default()
+            },
+        });
+    }
+}
+
+fn update_scramblers(
+    mut commands: Commands,
+    mut event_writer: EventWriter<HitEvent>,
+    mut scramblers: Query<(Entity, &Direction, &SpawnedAt, &mut Transform), With<Scrambler>>,
+    speed: Res<HazardSpeed>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    for (entity, &direction, spawned_at, mut transform) in scramblers.iter_mut() {
+        transform.translation += direction.to_vec3() * time_scale.delta_seconds(&time) * **speed;
+        transform.rotation *= Quat::from_rotation_z(time_scale.delta_seconds(&time) * -4.0);
+
+        if transform.translation.length() <= 70.0 {
+            commands.entity(entity).despawn_recursive();
+            event_writer.send(HitEvent {
+                hazard_type: HazardType::Scrambler,
+                from_direction: direction,
+                spawned_at: spawned_at.0,
+                source: entity,
+                barrage: None,
+            });
+        }
+    }
+}
@@ -2,7 +2,7 @@ use bevy::prelude::*;
 
 use crate::{AppState, GameAssets};
 
-use super::{spaceship::Health, Game};
+use super::{effects::HudElement, running, spaceship::Health, Game};
 
 #[derive(Component)]
 struct HealthDisplay;
@@ -12,10 +12,7 @@ pub struct HealthPlugin;
 impl Plugin for HealthPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(AppState::Playing), spawn_health_display)
-            .add_systems(
-                Update,
-                update_health_display.run_if(in_state(AppState::Playing)),
-            );
+            .add_systems(Update, update_health_display.run_if(running));
     }
 }
 
@@ -31,6 +28,7 @@ fn spawn_health_display(mut commands: Commands) {
             ..default()
         },
         HealthDisplay,
+        HudElement,
         Game,
     ));
 }
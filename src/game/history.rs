@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{save, utils::DirectionMode, AppState};
+
+use super::{
+    difficulty::Difficulty,
+    hazards::HazardType,
+    stats::{finish_stats, RunStats},
+};
+
+/// One line of the run history log.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Seconds since the Unix epoch.
+    pub date: u64,
+    pub difficulty: Difficulty,
+    /// Older runs were recorded before hazards could come from the diagonals.
+    #[serde(default)]
+    pub direction_mode: DirectionMode,
+    pub score: usize,
+    /// In seconds.
+    pub duration: f32,
+    pub seed: u64,
+    pub cause_of_death: Option<HazardType>,
+    /// Older runs were recorded before there were any assists.
+    #[serde(default)]
+    pub assisted: bool,
+}
+
+/// Totals worked out from every run in the history.
+pub struct Lifetime {
+    pub runs: Vec<RunRecord>,
+    pub total_play_time: f32,
+    pub median_score: Option<usize>,
+    pub most_common_killer: Option<HazardType>,
+}
+
+impl Lifetime {
+    /// Reads the whole history, which is only done when it's needed so startup isn't affected.
+    pub fn load() -> Self {
+        let runs: Vec<RunRecord> = save::load_log("history");
+
+        let total_play_time = runs.iter().map(|run| run.duration).sum();
+
+        let mut scores: Vec<usize> = runs.iter().map(|run| run.score).collect();
+        scores.sort_unstable();
+        let median_score = scores.get(scores.len() / 2).copied();
+
+        let mut kills: HashMap<HazardType, usize> = HashMap::new();
+        for killer in runs.iter().filter_map(|run| run.cause_of_death) {
+            *kills.entry(killer).or_default() += 1;
+        }
+        let most_common_killer = kills
+            .into_iter()
+            .max_by_key(|&(_, count)| count)
+            .map(|(killer, _)| killer);
+
+        Self {
+            runs,
+            total_play_time,
+            median_score,
+            most_common_killer,
+        }
+    }
+}
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        // Runs quit from the pause menu are abandoned rather than recorded.
+        app.add_systems(
+            OnTransition {
+                from: AppState::Playing,
+                to: AppState::GameOver,
+            },
+            record_run.after(finish_stats),
+        );
+    }
+}
+
+fn record_run(stats: Res<RunStats>) {
+    save::append(
+        "history",
+        &RunRecord {
+            date: save::unix_time(),
+            difficulty: stats.difficulty,
+            direction_mode: stats.direction_mode,
+            score: stats.score,
+            duration: stats.run_length,
+            seed: stats.seed,
+            cause_of_death: stats.killed_by,
+            assisted: stats.assisted,
+        },
+    );
+}
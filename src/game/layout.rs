@@ -0,0 +1,426 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    locale::Localized, palette::Role, save, settings::Settings, utils::Direction, AppState,
+    GameAssets,
+};
+
+use super::{
+    hazards::{HazardType, MissileConfig, RunRng},
+    running,
+    spaceship::{handles, HitOutcomeEvent, Invulnerable, Spaceship},
+    Game,
+};
+
+const SCRAMBLE_DURATION: f32 = 8.0;
+
+/// The things mounted on the faces of the spaceship.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Tool {
+    Drill,
+    Thruster,
+    Shield,
+    Cockpit,
+    /// Collects crates arriving at its face or either neighbouring face.
+    Magnet,
+    /// Blocks lasers like a shield and sends them back to destroy the satilite.
+    Reflector,
+}
+
+impl Tool {
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Drill => Self::Thruster,
+            Self::Thruster => Self::Shield,
+            Self::Shield => Self::Cockpit,
+            Self::Cockpit => Self::Magnet,
+            Self::Magnet => Self::Reflector,
+            Self::Reflector => Self::Drill,
+        }
+    }
+
+    /// The key of its name in the translation files.
+    pub const fn name_key(self) -> &'static str {
+        match self {
+            Self::Drill => "tool-drill",
+            Self::Thruster => "tool-thruster",
+            Self::Shield => "tool-shield",
+            Self::Cockpit => "tool-cockpit",
+            Self::Magnet => "tool-magnet",
+            Self::Reflector => "tool-reflector",
+        }
+    }
+}
+
+/// Which tool faces each side, starting with the side the spaceship is facing and going clockwise.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct ShipLayout(pub [Tool; 4]);
+
+impl Default for ShipLayout {
+    fn default() -> Self {
+        Self([Tool::Thruster, Tool::Shield, Tool::Cockpit, Tool::Drill])
+    }
+}
+
+impl ShipLayout {
+    /// The faces that meet hazards travelling towards `side` when the spaceship is facing `direction`.
+    ///
+    /// This is a single face when `side` lines up with one, or both faces beside the corner when it
+    /// is diagonal to them.
+    pub fn faces_at(&self, side: Direction, direction: Direction) -> Vec<usize> {
+        let mut aligned = Vec::new();
+        let mut beside = Vec::new();
+        let mut face = direction;
+        for index in 0..self.0.len() {
+            match (side.index() + 8 - face.index()) % 8 {
+                0 => aligned.push(index),
+                1 | 7 => beside.push(index),
+                _ => {}
+            }
+            face = face.rotate_cw();
+        }
+        if aligned.is_empty() {
+            beside
+        } else {
+            aligned
+        }
+    }
+
+    /// Whether every hazard that does damage has a tool to deal with it: a drill, a thruster, and a shield or reflector.
+    pub fn is_playable(&self) -> bool {
+        let has = |tool| self.0.contains(&tool);
+        has(Tool::Drill) && has(Tool::Thruster) && (has(Tool::Shield) || has(Tool::Reflector))
+    }
+
+    pub fn tools_at(&self, side: Direction, direction: Direction) -> Vec<Tool> {
+        self.faces_at(side, direction)
+            .into_iter()
+            .map(|face| self.0[face])
+            .collect()
+    }
+
+    /// Whether `tool` is on the face meeting `side` or either of its neighbours.
+    pub fn near(&self, tool: Tool, side: Direction, direction: Direction) -> bool {
+        [side, side.rotate_cw(), side.rotate_ccw()]
+            .into_iter()
+            .any(|side| self.tools_at(side, direction).contains(&tool))
+    }
+}
+
+/// The layout chosen in the hangar, which every run starts with.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct Loadout(pub ShipLayout);
+
+/// A spaceship whose layout has been shuffled by a scrambler, and will be restored to `original`.
+#[derive(Component)]
+pub struct Scrambled {
+    original: ShipLayout,
+    timer: Timer,
+}
+
+#[derive(Component)]
+struct ToolLabel(usize);
+
+#[derive(Component)]
+struct ScrambleDisplay;
+
+pub struct LayoutPlugin;
+
+impl Plugin for LayoutPlugin {
+    fn build(&self, app: &mut App) {
+        let mut loadout = save::load::<Loadout>("loadout");
+        if !loadout.0.is_playable() {
+            loadout = Loadout::default();
+        }
+        app.insert_resource(loadout)
+            .add_systems(OnEnter(AppState::Playing), spawn_scramble_display)
+            .add_systems(
+                Update,
+                (
+                    spawn_tool_labels,
+                    scramble_on_hit,
+                    unscramble,
+                    update_tool_labels,
+                    update_scramble_display,
+                )
+                    .chain()
+                    .run_if(running),
+            );
+    }
+}
+
+fn spawn_tool_labels(
+    mut commands: Commands,
+    spaceships: Query<(Entity, &ShipLayout), Added<ShipLayout>>,
+    assets: Res<GameAssets>,
+) {
+    for (spaceship, layout) in spaceships.iter() {
+        commands.entity(spaceship).with_children(|parent| {
+            let mut face = Direction::Up;
+            for index in 0..4 {
+                // Hazards travelling towards a face arrive from the opposite direction.
+                parent.spawn((
+                    Text2dBundle {
+                        text: Text::from_section(
+                            "",
+                            TextStyle {
+                                font: assets.font.clone(),
+                                font_size: 24.0,
+                                color: Color::WHITE,
+                            },
+                        ),
+                        transform: Transform::from_translation(face.to_vec3() * -130.0 + Vec3::Z),
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    },
+                    Localized::new(layout.0[index].name_key()),
+                    ToolLabel(index),
+                ));
+                face = face.rotate_cw();
+            }
+        });
+    }
+}
+
+fn scramble_on_hit(
+    mut commands: Commands,
+    mut outcome_events: EventReader<HitOutcomeEvent>,
+    mut spaceships: Query<(Entity, &mut ShipLayout, Option<&mut Scrambled>), With<Spaceship>>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    // `Scrambled` isn't inserted until the end of the frame, so remember the layout it will restore in the meantime.
+    let mut inserted = None;
+    for event in outcome_events.read() {
+        if event.hit.hazard_type != HazardType::Scrambler || event.success {
+            continue;
+        }
+        let Ok((entity, mut layout, scrambled)) = spaceships.get_single_mut() else {
+            continue;
+        };
+
+        let original = match (scrambled, inserted) {
+            (Some(mut scrambled), _) => {
+                scrambled.timer.reset();
+                scrambled.original
+            }
+            (None, Some(original)) => original,
+            (None, None) => {
+                commands.entity(entity).insert(Scrambled {
+                    original: *layout,
+                    timer: Timer::from_seconds(SCRAMBLE_DURATION, TimerMode::Once),
+                });
+                inserted = Some(*layout);
+                *layout
+            }
+        };
+
+        while *layout == original {
+            layout.0.shuffle(run_rng.rng());
+        }
+    }
+}
+
+fn unscramble(
+    mut commands: Commands,
+    mut spaceships: Query<(Entity, &mut ShipLayout, &mut Scrambled)>,
+    time: Res<Time>,
+) {
+    for (entity, mut layout, mut scrambled) in spaceships.iter_mut() {
+        if scrambled.timer.tick(time.delta()).finished() {
+            *layout = scrambled.original;
+            commands.entity(entity).remove::<Scrambled>();
+        }
+    }
+}
+
+/// The glyphs of every hazard `tool` deals with.
+fn glyphs(tool: Tool, missile_config: &MissileConfig) -> String {
+    HazardType::ALL
+        .into_iter()
+        .filter(|&hazard_type| {
+            handles(hazard_type, tool, missile_config)
+                || (tool == Tool::Magnet && hazard_type == HazardType::Crate)
+        })
+        .map(HazardType::glyph)
+        .collect()
+}
+
+fn update_tool_labels(
+    mut spaceships: Query<
+        (&ShipLayout, Has<Scrambled>, Has<Invulnerable>, &mut Sprite),
+        With<Spaceship>,
+    >,
+    mut labels: Query<(&ToolLabel, &mut Localized, &mut Visibility)>,
+    settings: Res<Settings>,
+    missile_config: Res<MissileConfig>,
+) {
+    let Ok((layout, scrambled, invulnerable, mut sprite)) = spaceships.get_single_mut() else {
+        return;
+    };
+
+    // The spaceship's art only shows the default layout, so label each face when it doesn't match.
+    let mut color = if scrambled {
+        settings.palette.color(Role::Scrambled)
+    } else {
+        Color::WHITE
+    };
+    // Without blinking, the spaceship fades out while it can't be damaged instead.
+    if invulnerable && settings.reduced_motion {
+        color.set_a(0.5);
+    }
+    if sprite.color != color {
+        sprite.color = color;
+    }
+    for (label, mut localized, mut visibility) in labels.iter_mut() {
+        let tool = layout.0[label.0];
+        localized.set_if_neq(if settings.shape_cues {
+            Localized::new("hud-tool-with-glyphs")
+                .with("glyphs", glyphs(tool, &missile_config))
+                .with_message("tool", tool.name_key())
+        } else {
+            Localized::new(tool.name_key())
+        });
+        *visibility = if *layout != ShipLayout::default() || settings.shape_cues {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn spawn_scramble_display(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    settings: Res<Settings>,
+) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: assets.font.clone(),
+                font_size: 30.0,
+                color: settings.palette.color(Role::Scrambled),
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            top: Val::Px(10.0),
+            ..default()
+        }),
+        Localized::new("hud-scrambled"),
+        ScrambleDisplay,
+        Game,
+    ));
+}
+
+fn update_scramble_display(
+    spaceships: Query<Option<&Scrambled>, With<Spaceship>>,
+    mut displays: Query<(&mut Localized, &mut Visibility), With<ScrambleDisplay>>,
+) {
+    let Ok(scrambled) = spaceships.get_single() else {
+        return;
+    };
+    for (mut localized, mut visibility) in displays.iter_mut() {
+        let Some(scrambled) = scrambled else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+        localized.set_if_neq(Localized::new("hud-scrambled").with(
+            "seconds",
+            format!("{:.1}", scrambled.timer.remaining_secs()),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::game::hazards::HitEvent;
+
+    #[test]
+    fn faces_at_finds_the_aligned_face() {
+        let layout = ShipLayout::default();
+        assert_eq!(layout.faces_at(Direction::Up, Direction::Up), [0]);
+        assert_eq!(layout.faces_at(Direction::Right, Direction::Up), [1]);
+        assert_eq!(layout.faces_at(Direction::Down, Direction::Up), [2]);
+        assert_eq!(layout.faces_at(Direction::Left, Direction::Up), [3]);
+        assert_eq!(layout.faces_at(Direction::Up, Direction::Right), [3]);
+    }
+
+    #[test]
+    fn faces_at_a_corner_are_both_beside_it() {
+        let layout = ShipLayout::default();
+        assert_eq!(layout.faces_at(Direction::UpRight, Direction::Up), [0, 1]);
+        assert_eq!(layout.faces_at(Direction::UpLeft, Direction::Up), [0, 3]);
+        assert_eq!(layout.faces_at(Direction::Up, Direction::UpLeft), [0, 1]);
+    }
+
+    #[test]
+    fn tools_at_follows_the_layout() {
+        let layout = ShipLayout::default();
+        assert_eq!(
+            layout.tools_at(Direction::DownLeft, Direction::Up),
+            [Tool::Cockpit, Tool::Drill]
+        );
+    }
+
+    #[test]
+    fn playable_layouts_have_every_counter() {
+        assert!(ShipLayout::default().is_playable());
+        assert!(
+            ShipLayout([Tool::Drill, Tool::Thruster, Tool::Reflector, Tool::Magnet]).is_playable()
+        );
+        assert!(
+            !ShipLayout([Tool::Drill, Tool::Thruster, Tool::Cockpit, Tool::Magnet]).is_playable()
+        );
+        assert!(!ShipLayout([Tool::Drill, Tool::Drill, Tool::Shield, Tool::Cockpit]).is_playable());
+        assert!(
+            !ShipLayout([Tool::Thruster, Tool::Shield, Tool::Cockpit, Tool::Reflector])
+                .is_playable()
+        );
+    }
+
+    fn scrambler_miss(source: Entity) -> HitOutcomeEvent {
+        HitOutcomeEvent {
+            hit: HitEvent {
+                hazard_type: HazardType::Scrambler,
+                from_direction: Direction::Up,
+                spawned_at: 0.0,
+                source,
+                barrage: None,
+            },
+            tool: Tool::Thruster,
+            success: false,
+            damage: 0,
+        }
+    }
+
+    #[test]
+    fn scramblers_together_keep_the_original_layout() {
+        let mut world = World::new();
+        world.init_resource::<Events<HitOutcomeEvent>>();
+        world.insert_resource(RunRng::new(0));
+        let spaceship = world.spawn((Spaceship, ShipLayout::default())).id();
+
+        let mut events = world.resource_mut::<Events<HitOutcomeEvent>>();
+        events.send(scrambler_miss(spaceship));
+        events.send(scrambler_miss(spaceship));
+        world.run_system_once(scramble_on_hit);
+
+        let spaceship = world.entity(spaceship);
+        assert_ne!(
+            *spaceship.get::<ShipLayout>().unwrap(),
+            ShipLayout::default()
+        );
+        assert_eq!(
+            spaceship.get::<Scrambled>().unwrap().original,
+            ShipLayout::default()
+        );
+    }
+}
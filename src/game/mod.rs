@@ -2,12 +2,19 @@ use std::f32::consts::PI;
 
 use bevy::prelude::*;
 
-use crate::{utils, AppState};
+use crate::{settings::Settings, utils, AppState};
 
+pub mod achievements;
+pub mod difficulty;
+pub mod effects;
 pub mod hazards;
 pub mod health;
+pub mod history;
+pub mod layout;
+pub mod progression;
 pub mod score;
 pub mod spaceship;
+pub mod stats;
 
 #[derive(Component)]
 struct Game;
@@ -15,6 +22,18 @@ struct Game;
 #[derive(Component)]
 struct Shaking(Timer);
 
+#[derive(States, Default, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+/// Whether a run is underway and not paused, for the systems that move it along.
+pub fn running(app_state: Res<State<AppState>>, pause_state: Res<State<PauseState>>) -> bool {
+    *app_state.get() == AppState::Playing && *pause_state.get() == PauseState::Running
+}
+
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
@@ -24,7 +43,16 @@ impl Plugin for GamePlugin {
             hazards::HazardsPlugin,
             score::ScorePlugin,
             health::HealthPlugin,
+            effects::EffectsPlugin,
+            layout::LayoutPlugin,
+            progression::ProgressionPlugin,
+            achievements::AchievementsPlugin,
+            stats::StatsPlugin,
+            history::HistoryPlugin,
         ))
+        .add_state::<PauseState>()
+        .init_resource::<difficulty::Difficulty>()
+        .init_resource::<utils::DirectionMode>()
         .add_systems(Update, handle_shake)
         .add_systems(OnExit(AppState::Playing), utils::despawn_with::<Game>);
     }
@@ -34,11 +62,14 @@ fn handle_shake(
     mut commands: Commands,
     mut query: Query<(Entity, &mut Transform, &mut Shaking)>,
     time: Res<Time>,
+    settings: Res<Settings>,
 ) {
     for (entity, mut transform, mut shaking) in query.iter_mut() {
         shaking.0.tick(time.delta());
         if shaking.0.just_finished() && shaking.0.mode() == TimerMode::Once {
             commands.entity(entity).remove::<Shaking>();
+        } else if !settings.shake() {
+            transform.scale = Vec3::ONE;
         } else {
             let progress = shaking.0.percent();
             transform.scale = Vec3::splat(f32::sin(progress * 2.0 * PI).mul_add(0.1, 1.0));
@@ -0,0 +1,343 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{save, AppState, Background, GameAssets};
+
+use super::{
+    layout::{ShipLayout, Tool},
+    running,
+    score::Score,
+    spaceship::{face_rotation, Spaceship},
+    Game,
+};
+
+/// Everything that can be bought in the shop with stardust.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Unlock {
+    CrimsonShip,
+    EmeraldShip,
+    GoldShip,
+    SparkTrail,
+    StardustTrail,
+    RainbowTrail,
+    NebulaBackground,
+    DeepSpaceBackground,
+    ExtraHeart,
+    LongPowerUps,
+}
+
+/// Only one unlock from each slot can be equipped at a time.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Slot {
+    Ship,
+    Trail,
+    Background,
+    Perk,
+}
+
+impl Unlock {
+    pub const ALL: [Self; 10] = [
+        Self::CrimsonShip,
+        Self::EmeraldShip,
+        Self::GoldShip,
+        Self::SparkTrail,
+        Self::StardustTrail,
+        Self::RainbowTrail,
+        Self::NebulaBackground,
+        Self::DeepSpaceBackground,
+        Self::ExtraHeart,
+        Self::LongPowerUps,
+    ];
+
+    pub const fn slot(self) -> Slot {
+        match self {
+            Self::CrimsonShip | Self::EmeraldShip | Self::GoldShip => Slot::Ship,
+            Self::SparkTrail | Self::StardustTrail | Self::RainbowTrail => Slot::Trail,
+            Self::NebulaBackground | Self::DeepSpaceBackground => Slot::Background,
+            Self::ExtraHeart | Self::LongPowerUps => Slot::Perk,
+        }
+    }
+
+    pub const fn price(self) -> usize {
+        match self {
+            Self::CrimsonShip => 50,
+            Self::EmeraldShip => 100,
+            Self::GoldShip => 250,
+            Self::SparkTrail => 75,
+            Self::StardustTrail => 150,
+            Self::RainbowTrail => 300,
+            Self::NebulaBackground => 100,
+            Self::DeepSpaceBackground => 200,
+            Self::ExtraHeart => 200,
+            Self::LongPowerUps => 150,
+        }
+    }
+
+    /// The key of its name in the translation files.
+    pub const fn name_key(self) -> &'static str {
+        match self {
+            Self::CrimsonShip => "unlock-crimson-ship",
+            Self::EmeraldShip => "unlock-emerald-ship",
+            Self::GoldShip => "unlock-gold-ship",
+            Self::SparkTrail => "unlock-spark-trail",
+            Self::StardustTrail => "unlock-stardust-trail",
+            Self::RainbowTrail => "unlock-rainbow-trail",
+            Self::NebulaBackground => "unlock-nebula-background",
+            Self::DeepSpaceBackground => "unlock-deep-space-background",
+            Self::ExtraHeart => "unlock-extra-heart",
+            Self::LongPowerUps => "unlock-long-power-ups",
+        }
+    }
+}
+
+/// Stardust and unlocks, which carry over between runs.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct Progress {
+    pub currency: usize,
+    owned: Vec<Unlock>,
+    equipped: Vec<Unlock>,
+}
+
+impl Progress {
+    pub fn owns(&self, unlock: Unlock) -> bool {
+        self.owned.contains(&unlock)
+    }
+
+    pub fn is_equipped(&self, unlock: Unlock) -> bool {
+        self.equipped.contains(&unlock)
+    }
+
+    pub fn equipped(&self, slot: Slot) -> Option<Unlock> {
+        self.equipped
+            .iter()
+            .copied()
+            .find(|unlock| unlock.slot() == slot)
+    }
+
+    /// Buys `unlock` if it can be afforded, returning whether it is now owned.
+    pub fn buy(&mut self, unlock: Unlock) -> bool {
+        if !self.owns(unlock) && self.currency >= unlock.price() {
+            self.currency -= unlock.price();
+            self.owned.push(unlock);
+        }
+        self.owns(unlock)
+    }
+
+    /// Equips `unlock` in place of whatever is in its slot, or unequips it if it already was.
+    pub fn toggle(&mut self, unlock: Unlock) {
+        if self.is_equipped(unlock) {
+            self.equipped.retain(|&equipped| equipped != unlock);
+        } else if self.owns(unlock) {
+            self.equipped
+                .retain(|equipped| equipped.slot() != unlock.slot());
+            self.equipped.push(unlock);
+        }
+    }
+
+    pub(crate) fn ship_texture(&self, assets: &GameAssets) -> Handle<Image> {
+        match self.equipped(Slot::Ship) {
+            Some(Unlock::CrimsonShip) => assets.crimson_spaceship.clone(),
+            Some(Unlock::EmeraldShip) => assets.emerald_spaceship.clone(),
+            Some(Unlock::GoldShip) => assets.gold_spaceship.clone(),
+            _ => assets.spaceship.clone(),
+        }
+    }
+
+    pub fn starting_health_bonus(&self) -> u32 {
+        u32::from(self.is_equipped(Unlock::ExtraHeart))
+    }
+
+    pub fn power_up_duration_scale(&self) -> f32 {
+        if self.is_equipped(Unlock::LongPowerUps) {
+            1.5
+        } else {
+            1.0
+        }
+    }
+
+    fn background_texture(&self, assets: &GameAssets) -> Handle<Image> {
+        match self.equipped(Slot::Background) {
+            Some(Unlock::NebulaBackground) => assets.nebula_background.clone(),
+            Some(Unlock::DeepSpaceBackground) => assets.deep_space_background.clone(),
+            _ => assets.background.clone(),
+        }
+    }
+}
+
+/// How much stardust the last run earned, for the game over screen.
+#[derive(Resource, Default)]
+pub struct LastReward(pub usize);
+
+#[derive(Resource, Deref, DerefMut)]
+struct TrailTimer(Timer);
+
+#[derive(Component)]
+struct TrailParticle(Timer);
+
+pub struct ProgressionPlugin;
+
+impl Plugin for ProgressionPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(save::load::<Progress>("progress"))
+            .init_resource::<LastReward>()
+            .insert_resource(TrailTimer(Timer::from_seconds(0.05, TimerMode::Repeating)))
+            .add_systems(
+                OnTransition {
+                    from: AppState::Playing,
+                    to: AppState::GameOver,
+                },
+                award_currency,
+            )
+            .add_systems(
+                Update,
+                (
+                    apply_background.run_if(resource_changed::<Progress>()),
+                    update_trail_particles,
+                ),
+            )
+            .add_systems(Update, spawn_trail_particles.run_if(running));
+    }
+}
+
+fn award_currency(
+    score: Res<Score>,
+    mut progress: ResMut<Progress>,
+    mut last_reward: ResMut<LastReward>,
+) {
+    last_reward.0 = score.score;
+    progress.currency += score.score;
+    save::store("progress", &*progress);
+}
+
+fn apply_background(
+    progress: Res<Progress>,
+    assets: Res<GameAssets>,
+    mut backgrounds: Query<&mut Handle<Image>, With<Background>>,
+) {
+    for mut texture in backgrounds.iter_mut() {
+        *texture = progress.background_texture(&assets);
+    }
+}
+
+fn spawn_trail_particles(
+    mut commands: Commands,
+    progress: Res<Progress>,
+    spaceships: Query<(&Transform, &ShipLayout), With<Spaceship>>,
+    mut timer: ResMut<TrailTimer>,
+    time: Res<Time>,
+) {
+    if !timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Some(trail) = progress.equipped(Slot::Trail) else {
+        return;
+    };
+    let Ok((transform, layout)) = spaceships.get_single() else {
+        return;
+    };
+
+    let color = match trail {
+        Unlock::SparkTrail => Color::ORANGE,
+        Unlock::StardustTrail => Color::WHITE,
+        _ => Color::hsl((time.elapsed_seconds() * 360.0) % 360.0, 1.0, 0.6),
+    };
+
+    // Trails come out of the thruster, or the back of the spaceship if it doesn't have one.
+    let face = layout
+        .0
+        .iter()
+        .position(|&tool| tool == Tool::Thruster)
+        .unwrap_or(0);
+    let offset = transform.rotation * face_rotation(face) * Vec3::NEG_Y * 110.0;
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::splat(8.0)),
+                ..default()
+            },
+            transform: Transform::from_translation(offset + Vec3::Z * 1.5),
+            ..default()
+        },
+        TrailParticle(Timer::from_seconds(0.5, TimerMode::Once)),
+        Game,
+    ));
+}
+
+fn update_trail_particles(
+    mut commands: Commands,
+    mut particles: Query<(Entity, &mut TrailParticle, &mut Transform, &mut Sprite)>,
+    time: Res<Time>,
+) {
+    for (entity, mut particle, mut transform, mut sprite) in particles.iter_mut() {
+        particle.0.tick(time.delta());
+        let outwards = transform
+            .translation
+            .truncate()
+            .normalize_or_zero()
+            .extend(0.0);
+        transform.translation += outwards * time.delta_seconds() * 150.0;
+        sprite.color.set_a(particle.0.percent_left());
+
+        if particle.0.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buy_spends_currency_once() {
+        let mut progress = Progress {
+            currency: 120,
+            ..default()
+        };
+        assert!(progress.buy(Unlock::CrimsonShip));
+        assert_eq!(progress.currency, 70);
+        assert!(progress.buy(Unlock::CrimsonShip));
+        assert_eq!(progress.currency, 70);
+    }
+
+    #[test]
+    fn buy_refuses_what_cannot_be_afforded() {
+        let mut progress = Progress {
+            currency: 100,
+            ..default()
+        };
+        assert!(!progress.buy(Unlock::GoldShip));
+        assert_eq!(progress.currency, 100);
+        assert!(!progress.owns(Unlock::GoldShip));
+    }
+
+    #[test]
+    fn toggle_swaps_within_a_slot() {
+        let mut progress = Progress {
+            currency: 1000,
+            ..default()
+        };
+        progress.buy(Unlock::CrimsonShip);
+        progress.buy(Unlock::EmeraldShip);
+        progress.buy(Unlock::SparkTrail);
+
+        progress.toggle(Unlock::CrimsonShip);
+        progress.toggle(Unlock::SparkTrail);
+        progress.toggle(Unlock::EmeraldShip);
+        assert_eq!(progress.equipped(Slot::Ship), Some(Unlock::EmeraldShip));
+        assert_eq!(progress.equipped(Slot::Trail), Some(Unlock::SparkTrail));
+        assert!(!progress.is_equipped(Unlock::CrimsonShip));
+
+        progress.toggle(Unlock::EmeraldShip);
+        assert_eq!(progress.equipped(Slot::Ship), None);
+    }
+
+    #[test]
+    fn toggle_ignores_what_is_not_owned() {
+        let mut progress = Progress::default();
+        progress.toggle(Unlock::RainbowTrail);
+        assert_eq!(progress.equipped(Slot::Trail), None);
+    }
+}
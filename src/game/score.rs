@@ -1,6 +1,17 @@
+use std::collections::HashMap;
+
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{locale::Localized, save, AppState, GameAssets};
+
+use super::{
+    difficulty::Difficulty,
+    effects::{ActiveEffects, HudElement},
+    stats::RunStats,
+};
 
-use crate::{AppState, GameAssets};
+const LEADERBOARD_SIZE: usize = 5;
 
 #[derive(Resource)]
 pub struct Score {
@@ -8,6 +19,57 @@ pub struct Score {
     pub high_score: usize,
 }
 
+/// A score on the leaderboard, flagged if it was set with an assist on.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(from = "SavedEntry")]
+pub struct LeaderboardEntry {
+    pub score: usize,
+    pub assisted: bool,
+}
+
+/// Leaderboards saved before there were assists only have the scores.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedEntry {
+    Score(usize),
+    Entry { score: usize, assisted: bool },
+}
+
+impl From<SavedEntry> for LeaderboardEntry {
+    fn from(entry: SavedEntry) -> Self {
+        match entry {
+            SavedEntry::Score(score) => Self {
+                score,
+                assisted: false,
+            },
+            SavedEntry::Entry { score, assisted } => Self { score, assisted },
+        }
+    }
+}
+
+/// The best scores for each difficulty preset, highest first.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct Leaderboards(HashMap<Difficulty, Vec<LeaderboardEntry>>);
+
+impl Leaderboards {
+    pub fn scores(&self, difficulty: Difficulty) -> &[LeaderboardEntry] {
+        self.0.get(&difficulty).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn high_score(&self, difficulty: Difficulty) -> usize {
+        self.scores(difficulty)
+            .first()
+            .map_or(0, |entry| entry.score)
+    }
+
+    fn record(&mut self, difficulty: Difficulty, entry: LeaderboardEntry) {
+        let scores = self.0.entry(difficulty).or_default();
+        let index = scores.partition_point(|other| other.score >= entry.score);
+        scores.insert(index, entry);
+        scores.truncate(LEADERBOARD_SIZE);
+    }
+}
+
 #[derive(Event)]
 pub struct ScoreEvent;
 
@@ -25,16 +87,36 @@ impl Plugin for ScorePlugin {
             score: 0,
             high_score: 0,
         })
+        .insert_resource(save::load::<Leaderboards>("leaderboards"))
         .add_event::<ScoreEvent>()
         .add_systems(OnExit(AppState::Splash), spawn_scoreboard)
-        .add_systems(Update, (update_score, update_scoreboard))
+        .add_systems(
+            Update,
+            (
+                sync_high_score.run_if(resource_changed::<Difficulty>()),
+                update_score,
+                update_scoreboard,
+            )
+                .chain(),
+        )
         .add_systems(OnEnter(AppState::Playing), (show_score, reset_score))
-        .add_systems(OnExit(AppState::Playing), hide_score);
+        .add_systems(OnExit(AppState::Playing), hide_score)
+        .add_systems(
+            OnTransition {
+                from: AppState::Playing,
+                to: AppState::GameOver,
+            },
+            record_score,
+        );
     }
 }
 
-fn update_score(mut score: ResMut<Score>, mut score_events: EventReader<ScoreEvent>) {
-    score.score += score_events.len();
+pub(super) fn update_score(
+    mut score: ResMut<Score>,
+    mut score_events: EventReader<ScoreEvent>,
+    effects: Res<ActiveEffects>,
+) {
+    score.score += score_events.len() * effects.score_multiplier();
     if score.score > score.high_score {
         score.high_score = score.score;
     }
@@ -45,78 +127,78 @@ fn reset_score(mut score: ResMut<Score>) {
     score.score = 0;
 }
 
+fn sync_high_score(
+    mut score: ResMut<Score>,
+    leaderboards: Res<Leaderboards>,
+    difficulty: Res<Difficulty>,
+) {
+    score.high_score = leaderboards.high_score(*difficulty);
+}
+
+fn record_score(
+    score: Res<Score>,
+    stats: Res<RunStats>,
+    mut leaderboards: ResMut<Leaderboards>,
+    difficulty: Res<Difficulty>,
+) {
+    leaderboards.record(
+        *difficulty,
+        LeaderboardEntry {
+            score: score.score,
+            assisted: stats.assisted,
+        },
+    );
+    save::store("leaderboards", &*leaderboards);
+}
+
 fn spawn_scoreboard(mut commands: Commands, assets: Res<GameAssets>) {
     commands
-        .spawn(NodeBundle {
-            style: Style {
-                position_type: PositionType::Absolute,
-                left: Val::Px(10.0),
-                bottom: Val::Px(10.0),
-                flex_direction: FlexDirection::Column,
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
                 ..default()
             },
-            ..default()
-        })
+            HudElement,
+        ))
         .with_children(|parent| {
+            let text_style = TextStyle {
+                font: assets.font.clone(),
+                font_size: 40.0,
+                color: Color::WHITE,
+            };
             parent.spawn((
-                TextBundle::from_sections(vec![
-                    TextSection {
-                        value: "Score: ".to_string(),
-                        style: TextStyle {
-                            font: assets.font.clone(),
-                            font_size: 40.0,
-                            color: Color::WHITE,
-                        },
-                    },
-                    TextSection {
-                        value: String::new(),
-                        style: TextStyle {
-                            font: assets.font.clone(),
-                            font_size: 40.0,
-                            color: Color::WHITE,
-                        },
-                    },
-                ])
-                .with_style(Style {
+                TextBundle::from_section("", text_style.clone()).with_style(Style {
                     display: Display::None,
                     ..default()
                 }),
+                Localized::new("hud-score").with("score", 0),
                 ScoreDisplay,
             ));
             parent.spawn((
-                TextBundle::from_sections(vec![
-                    TextSection {
-                        value: "High Score: ".to_string(),
-                        style: TextStyle {
-                            font: assets.font.clone(),
-                            font_size: 40.0,
-                            color: Color::WHITE,
-                        },
-                    },
-                    TextSection {
-                        value: String::new(),
-                        style: TextStyle {
-                            font: assets.font.clone(),
-                            font_size: 40.0,
-                            color: Color::WHITE,
-                        },
-                    },
-                ]),
+                TextBundle::from_section("", text_style),
+                Localized::new("hud-high-score").with("score", 0),
                 HighScoreDisplay,
             ));
         });
 }
 
 fn update_scoreboard(
-    score: ResMut<Score>,
-    mut score_text: Query<&mut Text, (With<ScoreDisplay>, Without<HighScoreDisplay>)>,
-    mut high_score_text: Query<&mut Text, (With<HighScoreDisplay>, Without<ScoreDisplay>)>,
+    score: Res<Score>,
+    mut score_text: Query<&mut Localized, (With<ScoreDisplay>, Without<HighScoreDisplay>)>,
+    mut high_score_text: Query<&mut Localized, (With<HighScoreDisplay>, Without<ScoreDisplay>)>,
 ) {
+    // Only touch the text when the score moves, so it isn't rebuilt every frame.
     for mut text in score_text.iter_mut() {
-        text.sections[1].value = score.score.to_string();
+        text.set_if_neq(Localized::new("hud-score").with("score", score.score));
     }
     for mut text in high_score_text.iter_mut() {
-        text.sections[1].value = score.high_score.to_string();
+        text.set_if_neq(Localized::new("hud-high-score").with("score", score.high_score));
     }
 }
 
@@ -131,3 +213,58 @@ fn hide_score(mut score: Query<&mut Style, With<ScoreDisplay>>) {
         style.display = Display::None;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: usize) -> LeaderboardEntry {
+        LeaderboardEntry {
+            score,
+            assisted: false,
+        }
+    }
+
+    fn scores(leaderboards: &Leaderboards, difficulty: Difficulty) -> Vec<usize> {
+        leaderboards
+            .scores(difficulty)
+            .iter()
+            .map(|entry| entry.score)
+            .collect()
+    }
+
+    #[test]
+    fn record_keeps_the_best_scores_highest_first() {
+        let mut leaderboards = Leaderboards::default();
+        for score in [3, 9, 1, 7, 5, 8, 2] {
+            leaderboards.record(Difficulty::Normal, entry(score));
+        }
+        assert_eq!(scores(&leaderboards, Difficulty::Normal), [9, 8, 7, 5, 3]);
+        assert_eq!(leaderboards.high_score(Difficulty::Normal), 9);
+    }
+
+    #[test]
+    fn record_puts_ties_after_older_scores() {
+        let mut leaderboards = Leaderboards::default();
+        leaderboards.record(Difficulty::Hard, entry(4));
+        leaderboards.record(
+            Difficulty::Hard,
+            LeaderboardEntry {
+                score: 4,
+                assisted: true,
+            },
+        );
+        let entries = leaderboards.scores(Difficulty::Hard);
+        assert!(!entries[0].assisted);
+        assert!(entries[1].assisted);
+    }
+
+    #[test]
+    fn record_keeps_presets_apart() {
+        let mut leaderboards = Leaderboards::default();
+        leaderboards.record(Difficulty::Easy, entry(10));
+        assert_eq!(leaderboards.high_score(Difficulty::Easy), 10);
+        assert_eq!(leaderboards.high_score(Difficulty::Insane), 0);
+        assert!(leaderboards.scores(Difficulty::Insane).is_empty());
+    }
+}
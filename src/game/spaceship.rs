@@ -1,27 +1,108 @@
+use std::collections::HashSet;
+use std::f32::consts::FRAC_PI_2;
 use std::time::Duration;
 
 use bevy::prelude::*;
 
-use crate::{utils::Direction, AppState, GameAssets};
+use crate::{
+    animation::{Animation, AnimationLibrary, Clip},
+    settings::Settings,
+    utils::{Direction, DirectionMode},
+    AppState, GameAssets,
+};
 
 use super::{
-    hazards::{HazardType, HitEvent},
+    difficulty::Difficulty,
+    effects::{ActiveEffects, Drawback, PowerUp},
+    hazards::{HazardType, HitEvent, MissileConfig},
+    layout::{Loadout, ShipLayout, Tool},
+    progression::Progress,
+    running,
     score::ScoreEvent,
     Game, Shaking,
 };
 
+/// How long the spaceship can't be damaged for after taking damage, in seconds.
+const INVULNERABILITY_DURATION: f32 = 1.0;
+const BLINK_RATE: f32 = 10.0;
+const SHIP_SIZE: f32 = 220.0;
+/// The most health a spaceship can ever have, whatever the preset and perks.
+const HEALTH_CAP: u32 = 8;
+/// What a crate is worth when the spaceship is already at full health.
+const FULL_HEALTH_CRATE_POINTS: usize = 3;
+
 #[derive(Component)]
 pub struct Spaceship;
 
+/// An animated part of the spaceship, drawn over the face holding its tool.
+#[derive(Component)]
+struct ShipPart;
+
 #[derive(Component)]
 pub struct Health(pub u32);
 
+#[derive(Component)]
+pub struct MaxHealth(pub u32);
+
+/// Sent by `handle_hits` once it has decided whether a hazard was dealt with by the right side.
+#[derive(Event)]
+pub struct HitOutcomeEvent {
+    pub hit: HitEvent,
+    /// The tool on the face the hazard arrived at.
+    pub tool: Tool,
+    pub success: bool,
+    /// How much health the hit cost.
+    pub damage: u32,
+}
+
+/// The hit that took the spaceship's last health.
+#[derive(Clone, Copy)]
+pub struct FinalHit {
+    pub hit: HitEvent,
+    /// Which way the spaceship was facing.
+    pub direction: Direction,
+    pub layout: ShipLayout,
+    /// The tool that met the hazard.
+    pub tool: Tool,
+    /// The tool that should have met it instead.
+    pub counter: Tool,
+}
+
+/// Added for a moment after the spaceship takes damage, so hazards arriving together can't drain all its health.
+#[derive(Component)]
+pub struct Invulnerable(Timer);
+
+/// How much health each type of hazard costs when it isn't dealt with, in the order of [`HazardType::ALL`].
+#[derive(Resource)]
+pub struct DamageConfig(pub [u32; 6]);
+
+impl Default for DamageConfig {
+    fn default() -> Self {
+        Self([1, 1, 2, 0, 1, 0])
+    }
+}
+
+impl DamageConfig {
+    pub fn damage(&self, hazard_type: HazardType) -> u32 {
+        let index = HazardType::ALL
+            .iter()
+            .position(|&other| other == hazard_type)
+            .unwrap();
+        self.0[index]
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct DeathRecap(pub Option<FinalHit>);
+
 #[derive(Bundle)]
 struct SpaceshipBundle {
     spaceship_marker: Spaceship,
     game_marker: Game,
     direction: Direction,
     health: Health,
+    max_health: MaxHealth,
+    layout: ShipLayout,
     #[bundle()]
     sprite: SpriteBundle,
 }
@@ -30,25 +111,50 @@ pub struct SpaceshipPlugin;
 
 impl Plugin for SpaceshipPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Playing), spawn_spaceship)
+        app.add_event::<HitOutcomeEvent>()
+            .init_resource::<DeathRecap>()
+            .init_resource::<DamageConfig>()
+            .add_systems(
+                OnEnter(AppState::Playing),
+                (spawn_spaceship, reset_death_recap),
+            )
             .add_systems(
                 Update,
-                (update_direction, apply_direction, handle_hits)
-                    .run_if(in_state(AppState::Playing)),
+                (
+                    update_direction,
+                    apply_direction,
+                    handle_hits,
+                    update_invulnerability,
+                    place_thruster_flames,
+                    animate_ship_parts.after(handle_hits),
+                    despawn_finished_parts,
+                    tint_ship_parts,
+                )
+                    .run_if(running),
             );
     }
 }
 
-fn spawn_spaceship(mut commands: Commands, assets: Res<GameAssets>) {
+fn spawn_spaceship(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    difficulty: Res<Difficulty>,
+    loadout: Res<Loadout>,
+    progress: Res<Progress>,
+) {
+    let preset = difficulty.preset();
+    let bonus = progress.starting_health_bonus();
     commands.spawn(SpaceshipBundle {
         spaceship_marker: Spaceship,
         game_marker: Game,
         direction: Direction::Up,
-        health: Health(3),
+        health: Health((preset.starting_health + bonus).min(HEALTH_CAP)),
+        max_health: MaxHealth((preset.max_health + bonus).min(HEALTH_CAP)),
+        layout: loadout.0,
         sprite: SpriteBundle {
-            texture: assets.spaceship.clone(),
+            texture: progress.ship_texture(&assets),
             sprite: Sprite {
-                custom_size: Some(Vec2 { x: 220.0, y: 220.0 }),
+                custom_size: Some(Vec2::splat(SHIP_SIZE)),
                 ..default()
             },
             transform: Transform::from_xyz(0.0, 0.0, 2.0),
@@ -57,17 +163,193 @@ fn spawn_spaceship(mut commands: Commands, assets: Res<GameAssets>) {
     });
 }
 
+/// The rotation that turns face 0 of the spaceship round to `face`, going clockwise.
+pub(super) fn face_rotation(face: usize) -> Quat {
+    Quat::from_rotation_z(-FRAC_PI_2 * face as f32)
+}
+
+/// `clip` laid over `face` of the spaceship.
+///
+/// The clip is drawn where `tool` sits in the spaceship's art, which shows the default layout, so it is turned to
+/// whichever face the tool is on now.
+fn ship_part(
+    library: &AnimationLibrary,
+    clip: Clip,
+    tool: Tool,
+    face: usize,
+    color: Color,
+) -> Option<(SpriteSheetBundle, Animation, ShipPart)> {
+    let (offset, size) = library.placement(clip)?;
+    let drawn_at = ShipLayout::default()
+        .0
+        .iter()
+        .position(|&drawn| drawn == tool)?;
+    let rotation = face_rotation(face) * face_rotation(drawn_at).inverse();
+    Some((
+        library.sheet(
+            clip,
+            TextureAtlasSprite {
+                color,
+                custom_size: Some(size * SHIP_SIZE),
+                ..default()
+            },
+            Transform::from_translation(rotation * (offset * SHIP_SIZE).extend(0.1))
+                .with_rotation(rotation),
+        ),
+        Animation::new(clip),
+        ShipPart,
+    ))
+}
+
+/// Lights a flame on every thruster, moving them whenever the layout changes.
+fn place_thruster_flames(
+    mut commands: Commands,
+    spaceships: Query<
+        (Entity, &ShipLayout, &Sprite, Option<&Children>),
+        (With<Spaceship>, Changed<ShipLayout>),
+    >,
+    parts: Query<&Animation, With<ShipPart>>,
+    library: Res<AnimationLibrary>,
+) {
+    for (entity, layout, sprite, children) in spaceships.iter() {
+        for &child in children.into_iter().flatten() {
+            if parts
+                .get(child)
+                .is_ok_and(|animation| animation.clip() == Clip::ThrusterFlame)
+            {
+                commands.entity(child).despawn_recursive();
+            }
+        }
+        commands.entity(entity).with_children(|parent| {
+            for (face, &tool) in layout.0.iter().enumerate() {
+                if tool != Tool::Thruster {
+                    continue;
+                }
+                if let Some(part) =
+                    ship_part(&library, Clip::ThrusterFlame, tool, face, sprite.color)
+                {
+                    parent.spawn(part);
+                }
+            }
+        });
+    }
+}
+
+/// Spins the drill or shimmers the shield on the face that dealt with a hazard.
+fn animate_ship_parts(
+    mut commands: Commands,
+    mut outcome_events: EventReader<HitOutcomeEvent>,
+    spaceships: Query<(Entity, &Direction, &ShipLayout, &Sprite), With<Spaceship>>,
+    library: Res<AnimationLibrary>,
+    settings: Res<Settings>,
+    missile_config: Res<MissileConfig>,
+) {
+    let Ok((entity, &direction, layout, sprite)) = spaceships.get_single() else {
+        return;
+    };
+    for event in outcome_events.read() {
+        if !event.success {
+            continue;
+        }
+        let side = event.hit.from_direction;
+        let (clip, tool, side) = match event.tool {
+            Tool::Drill => (Clip::DrillSpin, Tool::Drill, side),
+            Tool::Shield => (Clip::ShieldShimmer, Tool::Shield, side),
+            // Anything else that blocks shield hazards is the double shield power-up, on the opposite face.
+            tool if tool != Tool::Reflector
+                && handles(event.hit.hazard_type, Tool::Shield, &missile_config) =>
+            {
+                (
+                    Clip::ShieldShimmer,
+                    Tool::Shield,
+                    side.rotate_cw().rotate_cw(),
+                )
+            }
+            _ => continue,
+        };
+        if library.is_held(clip, &settings) {
+            continue;
+        }
+        commands.entity(entity).with_children(|parent| {
+            for face in layout.faces_at(side, direction) {
+                if layout.0[face] != tool {
+                    continue;
+                }
+                if let Some(part) = ship_part(&library, clip, tool, face, sprite.color) {
+                    parent.spawn(part);
+                }
+            }
+        });
+    }
+}
+
+fn despawn_finished_parts(
+    mut commands: Commands,
+    parts: Query<(Entity, &Animation), With<ShipPart>>,
+) {
+    for (entity, animation) in parts.iter() {
+        if animation.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Keeps the parts the same colour as the rest of the spaceship.
+fn tint_ship_parts(
+    spaceships: Query<&Sprite, (With<Spaceship>, Changed<Sprite>)>,
+    mut parts: Query<(&Parent, &mut TextureAtlasSprite), With<ShipPart>>,
+) {
+    for (parent, mut sprite) in parts.iter_mut() {
+        if let Ok(spaceship) = spaceships.get(parent.get()) {
+            sprite.color = spaceship.color;
+        }
+    }
+}
+
+fn reset_death_recap(mut death_recap: ResMut<DeathRecap>) {
+    death_recap.0 = None;
+}
+
+/// The tool which deals with `hazard_type`.
+const fn counter(hazard_type: HazardType, missile_config: &MissileConfig) -> Tool {
+    match hazard_type {
+        HazardType::Rock | HazardType::Scrambler => Tool::Drill,
+        HazardType::Ice => Tool::Thruster,
+        HazardType::Laser => Tool::Shield,
+        HazardType::Crate => Tool::Cockpit,
+        HazardType::Missile => missile_config.countered_by,
+    }
+}
+
+/// Whether `tool` deals with `hazard_type`, reflectors count as shields.
+pub(super) fn handles(hazard_type: HazardType, tool: Tool, missile_config: &MissileConfig) -> bool {
+    let counter = counter(hazard_type, missile_config);
+    tool == counter || (counter == Tool::Shield && tool == Tool::Reflector)
+}
+
 fn update_direction(
     input: Res<Input<KeyCode>>,
     mut directions: Query<&mut Direction, With<Spaceship>>,
+    effects: Res<ActiveEffects>,
+    direction_mode: Res<DirectionMode>,
+    settings: Res<Settings>,
 ) {
-    if input.any_just_pressed([KeyCode::A, KeyCode::Left]) {
+    let step = direction_mode.turn_step();
+    let ccw_keys = [settings.keys.turn_counterclockwise, KeyCode::Left];
+    let cw_keys = [settings.keys.turn_clockwise, KeyCode::Right];
+    let (ccw_keys, cw_keys) = if effects.has_drawback(Drawback::ReversedControls) {
+        (cw_keys, ccw_keys)
+    } else {
+        (ccw_keys, cw_keys)
+    };
+
+    if input.any_just_pressed(ccw_keys) {
         for mut direction in directions.iter_mut() {
-            *direction = direction.rotate_ccw();
+            *direction = direction.rotate_ccw_by(step);
         }
-    } else if input.any_just_pressed([KeyCode::D, KeyCode::Right]) {
+    } else if input.any_just_pressed(cw_keys) {
         for mut direction in directions.iter_mut() {
-            *direction = direction.rotate_cw();
+            *direction = direction.rotate_cw_by(step);
         }
     };
 }
@@ -78,63 +360,178 @@ fn apply_direction(mut spaceships: Query<(&Direction, &mut Transform), With<Spac
     transform.rotation = transform.rotation.slerp(target_quat, 0.3);
 }
 
-fn handle_hits(
+pub(super) fn handle_hits(
     mut commands: Commands,
     mut hit_event_reader: EventReader<HitEvent>,
     mut score_event_witer: EventWriter<ScoreEvent>,
-    mut spaceships: Query<(Entity, &Direction, &mut Health), With<Spaceship>>,
+    mut outcome_event_writer: EventWriter<HitOutcomeEvent>,
+    mut spaceships: Query<
+        (
+            Entity,
+            &Direction,
+            &ShipLayout,
+            &mut Health,
+            &MaxHealth,
+            Has<Invulnerable>,
+        ),
+        With<Spaceship>,
+    >,
     mut app_state: ResMut<NextState<AppState>>,
+    mut death_recap: ResMut<DeathRecap>,
+    missile_config: Res<MissileConfig>,
+    damage_config: Res<DamageConfig>,
+    effects: Res<ActiveEffects>,
 ) {
-    for event in hit_event_reader.read() {
-        let (entity, &direction, mut health) = spaceships.single_mut();
-        match event.hazard_type {
-            HazardType::Rock => {
-                if event.from_direction == direction.rotate_ccw() {
-                    score_event_witer.send(ScoreEvent);
+    let events: Vec<HitEvent> = hit_event_reader.read().copied().collect();
+    let Ok((_, &direction, &layout, .., mut invulnerable)) = spaceships.get_single() else {
+        return;
+    };
+    // Reflectors block lasers too, and the double shield power-up adds a second shield opposite the first.
+    let shielded = |from_direction: Direction| {
+        layout
+            .tools_at(from_direction, direction)
+            .iter()
+            .any(|tool| matches!(tool, Tool::Shield | Tool::Reflector))
+            || (effects.has(PowerUp::DoubleShield)
+                && layout
+                    .tools_at(from_direction.rotate_cw().rotate_cw(), direction)
+                    .contains(&Tool::Shield))
+    };
+    let blocked_barrages: HashSet<Entity> = events
+        .iter()
+        .filter(|event| shielded(event.from_direction))
+        .filter_map(|event| event.barrage)
+        .collect();
+    let mut penalised_barrages = HashSet::new();
+
+    for event in &events {
+        let (entity, &direction, &layout, mut health, max_health, _) = spaceships.single_mut();
+        // Hazards arriving at a corner can be dealt with by the face on either side of it.
+        let tools = layout.tools_at(event.from_direction, direction);
+        let tool = tools
+            .iter()
+            .copied()
+            .find(|&tool| handles(event.hazard_type, tool, &missile_config))
+            .unwrap_or(tools[0]);
+        let health_before = health.0;
+        let success = match event.hazard_type {
+            HazardType::Rock => tool == Tool::Drill,
+            HazardType::Ice => tool == Tool::Thruster,
+            HazardType::Laser => shielded(event.from_direction),
+            HazardType::Missile => {
+                if missile_config.countered_by == Tool::Shield {
+                    shielded(event.from_direction)
                 } else {
-                    health.0 -= 1;
-                    commands.entity(entity).insert(Shaking(Timer::new(
-                        Duration::from_millis(100),
-                        TimerMode::Once,
-                    )));
+                    tool == missile_config.countered_by
                 }
             }
-            HazardType::Ice => {
-                if event.from_direction == direction {
-                    score_event_witer.send(ScoreEvent);
-                } else {
-                    health.0 -= 1;
-                    commands.entity(entity).insert(Shaking(Timer::new(
-                        Duration::from_millis(100),
-                        TimerMode::Once,
-                    )));
-                }
+            HazardType::Scrambler => tool == Tool::Drill,
+            HazardType::Crate => {
+                tool == Tool::Cockpit || layout.near(Tool::Magnet, event.from_direction, direction)
             }
-            HazardType::Laser => {
-                if event.from_direction == direction.rotate_cw() {
+        };
+
+        // Scramblers aren't worth any points, they just don't shuffle the layout.
+        if success && event.hazard_type != HazardType::Scrambler {
+            score_event_witer.send(ScoreEvent);
+        }
+        if success {
+            match event.hazard_type {
+                // Destroying the satilite with its own laser is worth an extra point.
+                HazardType::Laser if tool == Tool::Reflector => {
                     score_event_witer.send(ScoreEvent);
-                } else {
-                    health.0 -= 1;
-                    commands.entity(entity).insert(Shaking(Timer::new(
-                        Duration::from_millis(100),
-                        TimerMode::Once,
-                    )));
                 }
-            }
-            HazardType::Crate => {
-                if event.from_direction == direction.rotate_cw().rotate_cw() {
-                    health.0 += 1;
-                    score_event_witer.send(ScoreEvent);
+                HazardType::Crate => {
+                    // Repairs aren't needed at full health, so the crate is worth points instead.
+                    if health.0 < max_health.0 {
+                        health.0 += 1;
+                    } else {
+                        for _ in 1..FULL_HEALTH_CRATE_POINTS {
+                            score_event_witer.send(ScoreEvent);
+                        }
+                    }
                     commands.entity(entity).insert(Shaking(Timer::new(
                         Duration::from_millis(200),
                         TimerMode::Once,
                     )));
                 }
+                _ => {}
+            }
+        } else {
+            // Blocking one beam of a barrage means the rest only cost one hit between them.
+            let grazed = event.barrage.is_some_and(|barrage| {
+                blocked_barrages.contains(&barrage) && !penalised_barrages.insert(barrage)
+            });
+            let damage = damage_config.damage(event.hazard_type);
+            if damage > 0 {
+                if !grazed && !invulnerable {
+                    health.0 = health.0.saturating_sub(damage);
+                    invulnerable = true;
+                    commands
+                        .entity(entity)
+                        .insert(Invulnerable(Timer::from_seconds(
+                            INVULNERABILITY_DURATION,
+                            TimerMode::Once,
+                        )));
+                }
+                commands.entity(entity).insert(Shaking(Timer::new(
+                    Duration::from_millis(100),
+                    TimerMode::Once,
+                )));
             }
         }
 
+        outcome_event_writer.send(HitOutcomeEvent {
+            hit: *event,
+            tool,
+            success,
+            damage: health_before.saturating_sub(health.0),
+        });
+
+        if health.0 == 0 && health_before > 0 {
+            death_recap.0 = Some(FinalHit {
+                hit: *event,
+                direction,
+                layout,
+                tool,
+                counter: counter(event.hazard_type, &missile_config),
+            });
+        }
         if health.0 == 0 {
             app_state.set(AppState::GameOver);
         }
     }
 }
+
+fn update_invulnerability(
+    mut commands: Commands,
+    mut spaceships: Query<(Entity, &mut Invulnerable, &mut Visibility), With<Spaceship>>,
+    time: Res<Time>,
+    settings: Res<Settings>,
+) {
+    for (entity, mut invulnerable, mut visibility) in spaceships.iter_mut() {
+        if invulnerable.0.tick(time.delta()).finished() {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<Invulnerable>();
+        } else if settings.blink_off(invulnerable.0.elapsed_secs(), BLINK_RATE) {
+            *visibility = Visibility::Hidden;
+        } else {
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faces_go_round_clockwise() {
+        let turned = |face| face_rotation(face) * Vec3::Y;
+        assert!(turned(0).abs_diff_eq(Vec3::Y, 1e-6));
+        assert!(turned(1).abs_diff_eq(Vec3::X, 1e-6));
+        assert!(turned(2).abs_diff_eq(Vec3::NEG_Y, 1e-6));
+        assert!(turned(3).abs_diff_eq(Vec3::NEG_X, 1e-6));
+        assert!(turned(4).abs_diff_eq(Vec3::Y, 1e-6));
+    }
+}
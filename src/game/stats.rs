@@ -0,0 +1,209 @@
+use std::collections::{HashMap, VecDeque};
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    save,
+    utils::{Direction, DirectionMode},
+    AppState,
+};
+
+use super::{
+    difficulty::Difficulty,
+    hazards::{GameClock, HazardType, RunRng, TimeScale},
+    running,
+    score::Score,
+    spaceship::{handle_hits, HitOutcomeEvent, Spaceship},
+};
+
+/// How far back hazards are counted when working out the hazard rate, in seconds.
+const RATE_WINDOW: f32 = 10.0;
+
+#[derive(Default, Clone, Copy, Serialize)]
+pub struct Tally {
+    pub handled: u32,
+    pub missed: u32,
+}
+
+impl std::ops::Add for Tally {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            handled: self.handled + other.handled,
+            missed: self.missed + other.missed,
+        }
+    }
+}
+
+impl Tally {
+    pub const fn total(self) -> u32 {
+        self.handled + self.missed
+    }
+
+    fn record(&mut self, success: bool) {
+        if success {
+            self.handled += 1;
+        } else {
+            self.missed += 1;
+        }
+    }
+}
+
+/// Statistics about the current (or most recent) run, which are saved as `last_run.json` when it ends.
+#[derive(Resource, Default, Serialize)]
+pub struct RunStats {
+    pub difficulty: Difficulty,
+    pub direction_mode: DirectionMode,
+    pub score: usize,
+    pub seed: u64,
+    /// In seconds.
+    pub run_length: f32,
+    pub hits: HashMap<HazardType, HashMap<Direction, Tally>>,
+    pub damage: HashMap<HazardType, u32>,
+    pub rotations: u32,
+    /// The most hazards to arrive within any [`RATE_WINDOW`], scaled to hazards per minute.
+    pub peak_hazard_rate: f32,
+    /// How long it took to turn to the right side after a hazard appeared, in seconds.
+    pub average_decision_time: Option<f32>,
+    /// The hazard that did the last damage, which is what ended the run.
+    pub killed_by: Option<HazardType>,
+    /// Whether the slow mode assist was on at any point in the run.
+    pub assisted: bool,
+    #[serde(skip)]
+    started_at: f32,
+    #[serde(skip)]
+    recent_hits: VecDeque<f32>,
+    #[serde(skip)]
+    last_turn: f32,
+    #[serde(skip)]
+    decision_times: Vec<f32>,
+}
+
+impl RunStats {
+    pub fn hits_by_type(&self, hazard_type: HazardType) -> Tally {
+        self.hits
+            .get(&hazard_type)
+            .into_iter()
+            .flat_map(HashMap::values)
+            .fold(Tally::default(), |total, &tally| total + tally)
+    }
+
+    pub fn hits_by_direction(&self, direction: Direction) -> Tally {
+        self.hits
+            .values()
+            .filter_map(|by_direction| by_direction.get(&direction))
+            .fold(Tally::default(), |total, &tally| total + tally)
+    }
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .add_systems(OnEnter(AppState::Playing), reset_stats)
+            .add_systems(
+                Update,
+                (track_rotations, track_outcomes, track_assists)
+                    .chain()
+                    .after(handle_hits)
+                    .run_if(running),
+            )
+            .add_systems(
+                OnTransition {
+                    from: AppState::Playing,
+                    to: AppState::GameOver,
+                },
+                finish_stats,
+            );
+    }
+}
+
+fn reset_stats(
+    mut stats: ResMut<RunStats>,
+    difficulty: Res<Difficulty>,
+    direction_mode: Res<DirectionMode>,
+    time: Res<Time>,
+) {
+    *stats = RunStats {
+        difficulty: *difficulty,
+        direction_mode: *direction_mode,
+        started_at: time.elapsed_seconds(),
+        ..default()
+    };
+}
+
+fn track_rotations(
+    mut stats: ResMut<RunStats>,
+    spaceships: Query<Ref<Direction>, With<Spaceship>>,
+    clock: Res<GameClock>,
+) {
+    for direction in spaceships.iter() {
+        if direction.is_changed() && !direction.is_added() {
+            stats.rotations += 1;
+            stats.last_turn = **clock;
+        }
+    }
+}
+
+fn track_outcomes(
+    mut stats: ResMut<RunStats>,
+    mut outcome_events: EventReader<HitOutcomeEvent>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    for event in outcome_events.read() {
+        let hit = event.hit;
+        stats
+            .hits
+            .entry(hit.hazard_type)
+            .or_default()
+            .entry(hit.from_direction)
+            .or_default()
+            .record(event.success);
+        if event.damage > 0 {
+            *stats.damage.entry(hit.hazard_type).or_default() += event.damage;
+            stats.killed_by = Some(hit.hazard_type);
+        }
+
+        // Decisions are measured the same way as the adaptive difficulty's reaction times.
+        if event.success && stats.last_turn > hit.spawned_at {
+            let decision_time = stats.last_turn - hit.spawned_at;
+            stats.decision_times.push(decision_time);
+        }
+
+        stats.recent_hits.push_back(now);
+    }
+
+    while stats
+        .recent_hits
+        .front()
+        .is_some_and(|&hit_at| now - hit_at > RATE_WINDOW)
+    {
+        stats.recent_hits.pop_front();
+    }
+    let rate = stats.recent_hits.len() as f32 * 60.0 / RATE_WINDOW;
+    stats.peak_hazard_rate = stats.peak_hazard_rate.max(rate);
+}
+
+fn track_assists(mut stats: ResMut<RunStats>, time_scale: Res<TimeScale>) {
+    if **time_scale < 1.0 && !stats.assisted {
+        stats.assisted = true;
+    }
+}
+
+pub(super) fn finish_stats(
+    mut stats: ResMut<RunStats>,
+    score: Res<Score>,
+    run_rng: Res<RunRng>,
+    time: Res<Time>,
+) {
+    stats.score = score.score;
+    stats.seed = run_rng.seed;
+    stats.run_length = time.elapsed_seconds() - stats.started_at;
+    stats.average_decision_time = (!stats.decision_times.is_empty())
+        .then(|| stats.decision_times.iter().sum::<f32>() / stats.decision_times.len() as f32);
+    save::store("last_run", &*stats);
+}
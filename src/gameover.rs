@@ -1,6 +1,20 @@
 use bevy::prelude::*;
 
-use crate::{game::score::Score, utils, AppState, GameAssets};
+use crate::{
+    game::{
+        difficulty::Difficulty,
+        hazards::HazardType,
+        progression::{LastReward, Progress},
+        score::{Leaderboards, Score},
+        spaceship::{DeathRecap, FinalHit},
+        stats::RunStats,
+    },
+    locale::{Locale, Localized},
+    palette::{Palette, Role},
+    settings::Settings,
+    utils::{self, Direction},
+    AppState, GameAssets,
+};
 
 #[derive(Component)]
 struct GameOver;
@@ -21,7 +35,19 @@ impl Plugin for GameOverPlugin {
     }
 }
 
-fn setup_menu(mut commands: Commands, assets: Res<GameAssets>, score: Res<Score>) {
+fn setup_menu(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    score: Res<Score>,
+    leaderboards: Res<Leaderboards>,
+    difficulty: Res<Difficulty>,
+    last_reward: Res<LastReward>,
+    progress: Res<Progress>,
+    stats: Res<RunStats>,
+    death_recap: Res<DeathRecap>,
+    settings: Res<Settings>,
+    locale: Res<Locale>,
+) {
     let button_style = Style {
         width: Val::Px(250.0),
         height: Val::Px(65.0),
@@ -53,33 +79,106 @@ fn setup_menu(mut commands: Commands, assets: Res<GameAssets>, score: Res<Score>
             GameOver,
         ))
         .with_children(|parent| {
-            parent.spawn(TextBundle::from_sections(vec![
-                TextSection {
-                    value: "Score: ".to_string(),
-                    style: TextStyle {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
                         font: assets.font.clone(),
                         font_size: 60.0,
                         color: Color::WHITE,
                     },
-                },
-                TextSection {
-                    value: score.score.to_string(),
-                    style: TextStyle {
+                ),
+                Localized::new("hud-score").with("score", score.score),
+            ));
+            let best_scores = leaderboards
+                .scores(*difficulty)
+                .iter()
+                .enumerate()
+                .map(|(index, entry)| {
+                    let flag = if entry.assisted { "*" } else { "" };
+                    format!("{}. {}{flag}", index + 1, entry.score)
+                })
+                .collect::<Vec<_>>()
+                .join("   ");
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
                         font: assets.font.clone(),
-                        font_size: 60.0,
+                        font_size: 30.0,
                         color: Color::WHITE,
                     },
-                },
-            ]));
-            parent.spawn(ImageBundle {
-                style: Style {
-                    width: Val::Auto,
-                    height: Val::Px(400.0),
+                ),
+                Localized::new("gameover-best-scores")
+                    .with_message("difficulty", difficulty.name_key())
+                    .with("scores", best_scores),
+            ));
+            if leaderboards
+                .scores(*difficulty)
+                .iter()
+                .any(|entry| entry.assisted)
+            {
+                parent.spawn((
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font: assets.font.clone(),
+                            font_size: 20.0,
+                            color: Color::WHITE,
+                        },
+                    ),
+                    Localized::new("gameover-assisted"),
+                ));
+            }
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 30.0,
+                        color: Color::GOLD,
+                    },
+                ),
+                Localized::new("gameover-stardust")
+                    .with("reward", last_reward.0)
+                    .with("total", progress.currency),
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
                     ..default()
-                },
-                image: UiImage::new(assets.broken_spaceship.clone()),
-                ..default()
-            });
+                })
+                .with_children(|parent| {
+                    for line in stats_lines(&stats, &locale) {
+                        parent.spawn(TextBundle::from_section(
+                            line,
+                            TextStyle {
+                                font: assets.font.clone(),
+                                font_size: 20.0,
+                                color: Color::WHITE,
+                            },
+                        ));
+                    }
+                });
+            match death_recap.0 {
+                Some(final_hit) => spawn_recap(parent, &assets, settings.palette, final_hit),
+                None => {
+                    parent.spawn(ImageBundle {
+                        style: Style {
+                            width: Val::Auto,
+                            height: Val::Px(250.0),
+                            ..default()
+                        },
+                        image: UiImage::new(assets.broken_spaceship.clone()),
+                        ..default()
+                    });
+                }
+            }
             parent
                 .spawn(NodeBundle {
                     style: Style { ..default() },
@@ -95,7 +194,10 @@ fn setup_menu(mut commands: Commands, assets: Res<GameAssets>, score: Res<Score>
                             GameOverButton::Retry,
                         ))
                         .with_children(|parent| {
-                            parent.spawn(TextBundle::from_section("Retry", text_style.clone()));
+                            parent.spawn((
+                                TextBundle::from_section("", text_style.clone()),
+                                Localized::new("gameover-retry"),
+                            ));
                         });
                     parent
                         .spawn((
@@ -106,15 +208,199 @@ fn setup_menu(mut commands: Commands, assets: Res<GameAssets>, score: Res<Score>
                             GameOverButton::Menu,
                         ))
                         .with_children(|parent| {
-                            parent.spawn(TextBundle::from_section(
-                                "Back to title",
-                                text_style.clone(),
+                            parent.spawn((
+                                TextBundle::from_section("", text_style.clone()),
+                                Localized::new("back-to-title"),
                             ));
                         });
                 });
         });
 }
 
+/// The message for where a hazard travelling in `direction` arrives from, as seen on screen.
+const fn arrives_from(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Up => "side-below",
+        Direction::UpLeft => "side-bottom-right",
+        Direction::Left => "side-right",
+        Direction::DownLeft => "side-top-right",
+        Direction::Down => "side-above",
+        Direction::DownRight => "side-top-left",
+        Direction::Right => "side-left",
+        Direction::UpRight => "side-bottom-left",
+    }
+}
+
+const fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Up => "direction-up",
+        Direction::UpLeft => "direction-up-left",
+        Direction::Left => "direction-left",
+        Direction::DownLeft => "direction-down-left",
+        Direction::Down => "direction-down",
+        Direction::DownRight => "direction-down-right",
+        Direction::Right => "direction-right",
+        Direction::UpRight => "direction-up-right",
+    }
+}
+
+/// Shows the spaceship as it was when it died, with the side that was hit and the side that should have been picked out in colour.
+fn spawn_recap(
+    parent: &mut ChildBuilder,
+    assets: &GameAssets,
+    palette: Palette,
+    final_hit: FinalHit,
+) {
+    parent.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: assets.font.clone(),
+                font_size: 20.0,
+                color: Color::WHITE,
+            },
+        ),
+        Localized::new("gameover-killed-by")
+            .with_message("hazard", final_hit.hit.hazard_type.name_key())
+            .with_message("side", arrives_from(final_hit.hit.from_direction))
+            .with_message("hit", final_hit.tool.name_key())
+            .with_message("needed", final_hit.counter.name_key()),
+    ));
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(330.0),
+                height: Val::Px(250.0),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(ImageBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(90.0),
+                    top: Val::Px(50.0),
+                    width: Val::Px(150.0),
+                    height: Val::Px(150.0),
+                    ..default()
+                },
+                image: UiImage::new(assets.broken_spaceship.clone()),
+                ..default()
+            });
+            let hit_faces = final_hit
+                .layout
+                .faces_at(final_hit.hit.from_direction, final_hit.direction);
+            let mut side = final_hit.direction;
+            for (face, tool) in final_hit.layout.0.into_iter().enumerate() {
+                // Each face meets hazards travelling towards it, so it sits on the opposite side.
+                let offset = side.to_vec3();
+                let left = 115.0 - offset.x * 115.0;
+                let top = 105.0 + offset.y * 105.0;
+                side = side.rotate_cw();
+
+                let color = if hit_faces.contains(&face) {
+                    palette.color(Role::Danger)
+                } else if tool == final_hit.counter {
+                    palette.color(Role::Safe)
+                } else {
+                    Color::rgba(0.3, 0.3, 0.3, 0.8)
+                };
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(left),
+                            top: Val::Px(top),
+                            width: Val::Px(100.0),
+                            height: Val::Px(40.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: color.into(),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "",
+                                TextStyle {
+                                    font: assets.font.clone(),
+                                    font_size: 20.0,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                            Localized::new(tool.name_key()),
+                        ));
+                    });
+            }
+        });
+}
+
+fn stats_lines(stats: &RunStats, locale: &Locale) -> Vec<String> {
+    let mut lines = vec![
+        locale.format(
+            "stats-run-length",
+            &[
+                (
+                    "length",
+                    format!(
+                        "{}:{:02}",
+                        stats.run_length as u32 / 60,
+                        stats.run_length as u32 % 60
+                    ),
+                ),
+                ("rotations", stats.rotations.to_string()),
+            ],
+        ),
+        locale.format(
+            "stats-hazard-rate",
+            &[
+                ("rate", format!("{:.0}", stats.peak_hazard_rate)),
+                (
+                    "time",
+                    stats
+                        .average_decision_time
+                        .map_or_else(|| "-".to_string(), |time| format!("{time:.2}s")),
+                ),
+            ],
+        ),
+    ];
+    if stats.assisted {
+        lines.push(locale.get("stats-assisted").to_string());
+    }
+    for hazard_type in HazardType::ALL {
+        let tally = stats.hits_by_type(hazard_type);
+        if tally.total() == 0 {
+            continue;
+        }
+        let damage = stats.damage.get(&hazard_type).copied().unwrap_or(0);
+        lines.push(locale.format(
+            "stats-hazard",
+            &[
+                ("hazard", locale.get(hazard_type.name_key()).to_string()),
+                ("handled", tally.handled.to_string()),
+                ("total", tally.total().to_string()),
+                ("damage", damage.to_string()),
+            ],
+        ));
+    }
+    // Diagonals only turn up in eight direction mode, so leave them out if nothing came from them.
+    let by_direction = Direction::ALL_EIGHT
+        .into_iter()
+        .map(|direction| (direction, stats.hits_by_direction(direction)))
+        .filter(|(direction, tally)| !direction.is_diagonal() || tally.total() > 0)
+        .map(|(direction, tally)| {
+            let name = locale.get(direction_name(direction));
+            format!("{name} {}/{}", tally.handled, tally.total())
+        })
+        .collect::<Vec<_>>()
+        .join("   ");
+    lines.push(locale.format("stats-by-direction", &[("directions", by_direction)]));
+    lines
+}
+
 fn menu_action(
     interaction_query: Query<(&Interaction, &GameOverButton), (Changed<Interaction>, With<Button>)>,
     mut app_state: ResMut<NextState<AppState>>,
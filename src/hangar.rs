@@ -0,0 +1,231 @@
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        layout::{Loadout, Tool},
+        progression::Progress,
+    },
+    locale::Localized,
+    save, utils, AppState, GameAssets,
+};
+
+#[derive(Component)]
+struct Hangar;
+
+#[derive(Component)]
+enum HangarButton {
+    /// Cycles the tool on a face of the loadout.
+    Face(usize),
+    Back,
+}
+
+#[derive(Component)]
+struct FaceLabel(usize);
+
+/// Shown while the loadout is missing a tool some hazard needs, which also keeps the player in the hangar.
+#[derive(Component)]
+struct MissingToolsWarning;
+
+fn warning_display(loadout: &Loadout) -> Display {
+    if loadout.0.is_playable() {
+        Display::None
+    } else {
+        Display::Flex
+    }
+}
+
+pub struct HangarPlugin;
+
+impl Plugin for HangarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Hangar), setup_hangar)
+            .add_systems(Update, hangar_action.run_if(in_state(AppState::Hangar)))
+            .add_systems(
+                OnExit(AppState::Hangar),
+                (utils::despawn_with::<Hangar>, save_loadout),
+            );
+    }
+}
+
+fn setup_hangar(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    loadout: Res<Loadout>,
+    progress: Res<Progress>,
+) {
+    let button_style = Style {
+        width: Val::Px(250.0),
+        height: Val::Px(65.0),
+        margin: UiRect::all(Val::Px(20.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    let text_style = TextStyle {
+        font: assets.font.clone(),
+        font_size: 40.0,
+        color: Color::BLACK,
+    };
+
+    let small_text_style = TextStyle {
+        font: assets.font.clone(),
+        font_size: 20.0,
+        color: Color::WHITE,
+    };
+
+    // Faces in loadout order sit below, left of, above and right of the spaceship facing up.
+    let face_positions = [(210.0, 410.0), (0.0, 205.0), (210.0, 0.0), (420.0, 205.0)];
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            Hangar,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                Localized::new("hangar-title"),
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(600.0),
+                        height: Val::Px(460.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(ImageBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(190.0),
+                            top: Val::Px(120.0),
+                            width: Val::Px(220.0),
+                            height: Val::Px(220.0),
+                            ..default()
+                        },
+                        image: UiImage::new(progress.ship_texture(&assets)),
+                        ..default()
+                    });
+                    for (face, (left, top)) in face_positions.into_iter().enumerate() {
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        position_type: PositionType::Absolute,
+                                        left: Val::Px(left),
+                                        top: Val::Px(top),
+                                        width: Val::Px(180.0),
+                                        height: Val::Px(50.0),
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+                                    ..default()
+                                },
+                                HangarButton::Face(face),
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    TextBundle::from_section(
+                                        "",
+                                        TextStyle {
+                                            font_size: 30.0,
+                                            ..text_style.clone()
+                                        },
+                                    ),
+                                    Localized::new(loadout.0 .0[face].name_key()),
+                                    FaceLabel(face),
+                                ));
+                            });
+                    }
+                });
+            for key in ["hangar-help", "hangar-magnet", "hangar-reflector"] {
+                parent.spawn((
+                    TextBundle::from_section("", small_text_style.clone()),
+                    Localized::new(key),
+                ));
+            }
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        color: Color::RED,
+                        ..small_text_style.clone()
+                    },
+                )
+                .with_style(Style {
+                    display: warning_display(&loadout),
+                    ..default()
+                }),
+                Localized::new("hangar-missing-tools"),
+                MissingToolsWarning,
+            ));
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: button_style.clone(),
+                        ..default()
+                    },
+                    HangarButton::Back,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section("", text_style.clone()),
+                        Localized::new("back"),
+                    ));
+                });
+        });
+}
+
+fn hangar_action(
+    interaction_query: Query<(&Interaction, &HangarButton), (Changed<Interaction>, With<Button>)>,
+    mut face_labels: Query<(&FaceLabel, &mut Localized)>,
+    mut warnings: Query<&mut Style, With<MissingToolsWarning>>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut loadout: ResMut<Loadout>,
+) {
+    for (interaction, hangar_button_action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match hangar_button_action {
+                HangarButton::Face(face) => {
+                    let tool: &mut Tool = &mut loadout.0 .0[*face];
+                    *tool = tool.next();
+                    for (label, mut localized) in face_labels.iter_mut() {
+                        if label.0 == *face {
+                            *localized = Localized::new(tool.name_key());
+                        }
+                    }
+                    for mut style in warnings.iter_mut() {
+                        style.display = warning_display(&loadout);
+                    }
+                }
+                HangarButton::Back if loadout.0.is_playable() => app_state.set(AppState::Menu),
+                HangarButton::Back => {}
+            }
+        }
+    }
+}
+
+fn save_loadout(loadout: Res<Loadout>) {
+    save::store("loadout", &*loadout);
+}
@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
+use bevy::window::PrimaryWindow;
+
+/// The width and height of the play area, which everything in the game is laid out for.
+pub const PLAY_AREA: f32 = 800.0;
+
+/// Comfortably bigger than any window, so the bars always reach the edges.
+const BAR_SIZE: f32 = 10000.0;
+
+pub struct LetterboxPlugin;
+
+impl Plugin for LetterboxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_bars)
+            .add_systems(Update, scale_ui);
+    }
+}
+
+/// A camera that always fits the whole play area in the window, whatever its shape.
+pub fn camera() -> Camera2dBundle {
+    let mut camera = Camera2dBundle::default();
+    camera.projection.scaling_mode = ScalingMode::AutoMin {
+        min_width: PLAY_AREA,
+        min_height: PLAY_AREA,
+    };
+    camera
+}
+
+/// Covers everything outside the play area, so hazards stay hidden until they would have come on screen.
+fn spawn_bars(mut commands: Commands) {
+    let offset = (PLAY_AREA + BAR_SIZE) / 2.0;
+    for position in [
+        Vec2::new(-offset, 0.0),
+        Vec2::new(offset, 0.0),
+        Vec2::new(0.0, -offset),
+        Vec2::new(0.0, offset),
+    ] {
+        commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: Color::BLACK,
+                custom_size: Some(Vec2::splat(BAR_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_translation(position.extend(500.0)),
+            ..default()
+        });
+    }
+}
+
+/// Scales the UI along with the play area.
+fn scale_ui(
+    windows: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let scale = f64::from(window.width().min(window.height()) / PLAY_AREA);
+    if ui_scale.0 != scale {
+        ui_scale.0 = scale;
+    }
+}
@@ -0,0 +1,239 @@
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+
+use crate::{locale::Localized, utils, AppState, GameAssets};
+
+#[derive(Component)]
+struct LoadingScreen;
+
+#[derive(Component)]
+struct ProgressFill;
+
+/// Lists the assets that couldn't be loaded, hidden until one fails.
+#[derive(Component)]
+struct FailureText;
+
+#[derive(Component)]
+struct ContinueButton;
+
+/// The assets that couldn't be loaded so far.
+#[derive(Resource, Default)]
+struct LoadFailures(Vec<String>);
+
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LoadFailures>()
+            .add_systems(OnEnter(AppState::Loading), setup_loading)
+            .add_systems(
+                Update,
+                (check_loading, show_failures, continue_action)
+                    .chain()
+                    .run_if(in_state(AppState::Loading)),
+            )
+            .add_systems(
+                OnExit(AppState::Loading),
+                utils::despawn_with::<LoadingScreen>,
+            );
+    }
+}
+
+/// Nothing has loaded yet, so everything here uses Bevy's built-in font.
+fn setup_loading(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            LoadingScreen,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(400.0),
+                        height: Val::Px(30.0),
+                        padding: UiRect::all(Val::Px(4.0)),
+                        ..default()
+                    },
+                    background_color: Color::rgba(1.0, 1.0, 1.0, 0.2).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(0.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: Color::GOLD.into(),
+                            ..default()
+                        },
+                        ProgressFill,
+                    ));
+                });
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::RED,
+                        ..default()
+                    },
+                )
+                .with_text_alignment(TextAlignment::Center)
+                .with_style(Style {
+                    max_width: Val::Px(600.0),
+                    margin: UiRect::all(Val::Px(20.0)),
+                    display: Display::None,
+                    ..default()
+                }),
+                FailureText,
+            ));
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(250.0),
+                            height: Val::Px(50.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            display: Display::None,
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    ContinueButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font_size: 24.0,
+                                color: Color::BLACK,
+                                ..default()
+                            },
+                        ),
+                        Localized::new("loading-continue"),
+                    ));
+                });
+        });
+}
+
+/// Fills the progress bar as assets finish loading, and moves on once they all have.
+/// Any that fail are kept in `LoadFailures`, and the player can choose whether to carry on without them.
+fn check_loading(
+    assets: Res<GameAssets>,
+    asset_server: Res<AssetServer>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut fill: Query<&mut Style, With<ProgressFill>>,
+    mut failures: ResMut<LoadFailures>,
+) {
+    let handles = assets.handles();
+    let mut loaded = 0;
+    let mut failed = Vec::new();
+    for handle in &handles {
+        match asset_server.get_load_state(handle.id()) {
+            Some(LoadState::Loaded) => loaded += 1,
+            Some(LoadState::Failed) => failed.push(
+                asset_server
+                    .get_path(handle.id())
+                    .map_or_else(|| "unknown asset".to_string(), |path| path.to_string()),
+            ),
+            _ => {}
+        }
+    }
+
+    for mut style in fill.iter_mut() {
+        style.width = Val::Percent((loaded + failed.len()) as f32 / handles.len() as f32 * 100.0);
+    }
+
+    if failed.is_empty() && loaded == handles.len() {
+        app_state.set(AppState::Splash);
+    }
+    if failures.0 != failed {
+        failures.0 = failed;
+    }
+}
+
+/// Lists the failed assets and shows the continue button, leaving both hidden while nothing has failed.
+fn show_failures(
+    mut commands: Commands,
+    failures: Res<LoadFailures>,
+    mut failure_text: Query<(Entity, &mut Style), (With<FailureText>, Without<ContinueButton>)>,
+    mut continue_button: Query<&mut Style, (With<ContinueButton>, Without<FailureText>)>,
+) {
+    if !failures.is_changed() || failures.0.is_empty() {
+        return;
+    }
+
+    let assets = failures.0.join(", ");
+    error!("Failed to load {}", assets);
+    for (entity, mut style) in failure_text.iter_mut() {
+        commands
+            .entity(entity)
+            .insert(Localized::new("loading-failed").with("assets", assets.clone()));
+        style.display = Display::Flex;
+    }
+    for mut style in continue_button.iter_mut() {
+        style.display = Display::Flex;
+    }
+}
+
+fn continue_action(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ContinueButton>)>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            app_state.set(AppState::Splash);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn loading_screen() -> World {
+        let mut world = World::new();
+        world.init_resource::<LoadFailures>();
+        world.run_system_once(setup_loading);
+        world
+    }
+
+    fn failure_text(world: &mut World) -> (Display, bool) {
+        let (style, localized) = world
+            .query_filtered::<(&Style, Option<&Localized>), With<FailureText>>()
+            .single(world);
+        (style.display, localized.is_some())
+    }
+
+    #[test]
+    fn clean_load_leaves_failures_hidden() {
+        let mut world = loading_screen();
+        world.run_system_once(show_failures);
+        assert_eq!(failure_text(&mut world), (Display::None, false));
+    }
+
+    #[test]
+    fn failures_are_listed() {
+        let mut world = loading_screen();
+        world.resource_mut::<LoadFailures>().0 = vec!["rock.png".to_string()];
+        world.run_system_once(show_failures);
+        assert_eq!(failure_text(&mut world), (Display::Flex, true));
+    }
+}
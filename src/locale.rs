@@ -0,0 +1,260 @@
+//! Translations, read from the `.lang` files in `assets/locales`.
+//!
+//! These use a deliberately small format, not Fluent:
+//!
+//! - Each message is one line of `key = message`, split at the first `=`, with spaces around both trimmed.
+//! - Blank lines and lines starting with `#` are skipped.
+//! - `{ $name }` is replaced with the value given for `name`, exactly as written with one space either side.
+//!
+//! There are no plurals, selectors, multiline messages or escapes, so write each variant as its own key.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy::text::update_text2d_layout;
+use bevy::ui::UiSystem;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+}
+
+impl Language {
+    pub const ALL: [Self; 2] = [Self::English, Self::French];
+
+    /// The language's name in itself, so it can be found without reading the current one.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::French => "Français",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&language| language == self);
+        Self::ALL[index.map_or(0, |index| (index + 1) % Self::ALL.len())]
+    }
+
+    /// The translation file, built in so there is nothing to wait for on the web.
+    const fn source(self) -> &'static str {
+        match self {
+            Self::English => include_str!("../assets/locales/en.lang"),
+            Self::French => include_str!("../assets/locales/fr.lang"),
+        }
+    }
+}
+
+/// Every translation, and the language currently in use.
+#[derive(Resource)]
+pub struct Locale {
+    language: Language,
+    translations: HashMap<Language, HashMap<&'static str, &'static str>>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        let translations: HashMap<_, _> = Language::ALL
+            .into_iter()
+            .map(|language| (language, parse(language)))
+            .collect();
+
+        let english = &translations[&Language::English];
+        for (language, messages) in &translations {
+            let mut missing: Vec<_> = english
+                .keys()
+                .filter(|key| !messages.contains_key(*key))
+                .collect();
+            if !missing.is_empty() {
+                missing.sort_unstable();
+                warn!("{language:?} is missing {missing:?}, falling back to English");
+            }
+        }
+
+        Self {
+            language: Language::default(),
+            translations,
+        }
+    }
+}
+
+impl Locale {
+    /// The message for `key` in the current language, or in English if it hasn't been translated.
+    pub fn get<'a>(&self, key: &'a str) -> &'a str {
+        [self.language, Language::English]
+            .into_iter()
+            .find_map(|language| self.translations[&language].get(key).copied())
+            .unwrap_or_else(|| {
+                error!("No message for {key}");
+                key
+            })
+    }
+
+    /// Like [`Locale::get`], with each `{ $name }` in the message replaced by its value.
+    pub fn format(&self, key: &str, args: &[(&str, String)]) -> String {
+        let mut message = self.get(key).to_string();
+        for (name, value) in args {
+            message = message.replace(&format!("{{ ${name} }}"), value);
+        }
+        message
+    }
+
+    /// The text for `localized` in the current language.
+    pub fn localize(&self, localized: &Localized) -> String {
+        let args: Vec<_> = localized
+            .args
+            .iter()
+            .map(|(name, arg)| {
+                let value = match arg {
+                    Arg::Text(text) => text.clone(),
+                    Arg::Message(key) => self.get(key).to_string(),
+                };
+                (*name, value)
+            })
+            .collect();
+        self.format(&localized.key, &args)
+    }
+}
+
+/// Reads the `key = message` lines of a translation file, skipping blank lines and `#` comments.
+fn parse(language: Language) -> HashMap<&'static str, &'static str> {
+    language
+        .source()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let message = line.split_once('=');
+            if message.is_none() {
+                error!("Invalid line in {language:?} translation: {line}");
+            }
+            message.map(|(key, value)| (key.trim(), value.trim()))
+        })
+        .collect()
+}
+
+/// Text that is kept in the current language, filling the first section of the entity's `Text`.
+/// Keys can come from data files as well as the code, so they aren't always `'static`.
+#[derive(Component, Clone, PartialEq, Eq)]
+pub struct Localized {
+    key: Cow<'static, str>,
+    args: Vec<(&'static str, Arg)>,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+enum Arg {
+    Text(String),
+    /// The key of a message, which is translated along with the rest.
+    Message(Cow<'static, str>),
+}
+
+impl Localized {
+    pub fn new(key: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            key: key.into(),
+            args: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.args.push((name, Arg::Text(value.to_string())));
+        self
+    }
+
+    /// Like [`Localized::with`], filling `name` with the message for `key`.
+    pub fn with_message(mut self, name: &'static str, key: impl Into<Cow<'static, str>>) -> Self {
+        self.args.push((name, Arg::Message(key.into())));
+        self
+    }
+}
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Locale>()
+            .add_systems(
+                Update,
+                switch_language.run_if(resource_changed::<Settings>()),
+            )
+            .add_systems(
+                PostUpdate,
+                localize_text
+                    .before(UiSystem::Layout)
+                    .before(update_text2d_layout),
+            );
+    }
+}
+
+fn switch_language(mut locale: ResMut<Locale>, settings: Res<Settings>) {
+    if locale.language != settings.language {
+        locale.language = settings.language;
+    }
+}
+
+/// Rewrites text when it is spawned or changed, and all of it when the language changes.
+fn localize_text(locale: Res<Locale>, mut texts: Query<(Ref<Localized>, &mut Text)>) {
+    for (localized, mut text) in texts.iter_mut() {
+        if locale.is_changed() || localized.is_changed() {
+            text.sections[0].value = locale.localize(&localized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locale(language: Language) -> Locale {
+        Locale {
+            language,
+            translations: HashMap::from([
+                (
+                    Language::English,
+                    HashMap::from([("greeting", "Hello { $name }"), ("farewell", "Goodbye")]),
+                ),
+                (
+                    Language::French,
+                    HashMap::from([("greeting", "Bonjour { $name }")]),
+                ),
+            ]),
+        }
+    }
+
+    #[test]
+    fn get_uses_the_current_language() {
+        assert_eq!(
+            locale(Language::French).get("greeting"),
+            "Bonjour { $name }"
+        );
+        assert_eq!(locale(Language::English).get("greeting"), "Hello { $name }");
+    }
+
+    #[test]
+    fn get_falls_back_to_english() {
+        assert_eq!(locale(Language::French).get("farewell"), "Goodbye");
+    }
+
+    #[test]
+    fn get_falls_back_to_the_key() {
+        assert_eq!(locale(Language::French).get("missing"), "missing");
+    }
+
+    #[test]
+    fn localize_fills_in_arguments() {
+        let localized = Localized::new("greeting").with("name", "Ada");
+        assert_eq!(locale(Language::French).localize(&localized), "Bonjour Ada");
+    }
+
+    #[test]
+    fn every_translation_file_parses() {
+        for language in Language::ALL {
+            assert!(!parse(language).is_empty(), "{language:?} has no messages");
+        }
+    }
+}
@@ -1,21 +1,40 @@
-#![allow(clippy::type_complexity)]
+#![allow(clippy::type_complexity, clippy::too_many_arguments)]
 
 use std::f32::consts::PI;
 
 use bevy::prelude::*;
-use bevy::window::WindowResolution;
+use bevy::window::{WindowResizeConstraints, WindowResolution};
 
+mod achievements;
+mod animation;
 mod game;
 mod gameover;
+mod hangar;
+mod letterbox;
+mod loading;
+mod locale;
 mod menu;
+mod music;
+mod palette;
+mod pause;
+mod save;
+mod settings;
+mod shop;
+mod sound;
 mod splash;
+mod statistics;
 mod utils;
 
 #[derive(States, Default, Debug, Hash, PartialEq, Eq, Clone, Copy)]
 enum AppState {
     #[default]
+    Loading,
     Splash,
     Menu,
+    Hangar,
+    Shop,
+    Achievements,
+    Statistics,
     Playing,
     GameOver,
 }
@@ -23,18 +42,22 @@ enum AppState {
 #[derive(Resource)]
 struct GameAssets {
     spaceship: Handle<Image>,
+    crimson_spaceship: Handle<Image>,
+    emerald_spaceship: Handle<Image>,
+    gold_spaceship: Handle<Image>,
     broken_spaceship: Handle<Image>,
-    rock_astroid: Handle<Image>,
     broken_rock_astroid: Handle<Image>,
-    ice_astroid: Handle<Image>,
     background: Handle<Image>,
+    nebula_background: Handle<Image>,
+    deep_space_background: Handle<Image>,
     font: Handle<Font>,
     bevy_logo: Handle<Image>,
     game_logo: Handle<Image>,
     clubbo: Handle<Image>,
-    satilite_idle: Handle<Image>,
     satilite_charging: Handle<Image>,
     laser: Handle<Image>,
+    /// Every image in `assets/animations.json`, so clips can be built as soon as loading finishes.
+    animation_sheets: Vec<Handle<Image>>,
     heart: Handle<Image>,
     health_crate: Handle<Image>,
 }
@@ -45,54 +68,139 @@ impl FromWorld for GameAssets {
 
         Self {
             spaceship: asset_server.load("ship.png"),
+            crimson_spaceship: asset_server.load("ship-crimson.png"),
+            emerald_spaceship: asset_server.load("ship-emerald.png"),
+            gold_spaceship: asset_server.load("ship-gold.png"),
             broken_spaceship: asset_server.load("ship-broken.png"),
-            rock_astroid: asset_server.load("rock.png"),
             broken_rock_astroid: asset_server.load("rock-broken.png"),
-            ice_astroid: asset_server.load("ice.png"),
             background: asset_server.load("background.png"),
+            nebula_background: asset_server.load("background-nebula.png"),
+            deep_space_background: asset_server.load("background-deep-space.png"),
             font: asset_server.load("Overpass-SemiBold.ttf"),
             bevy_logo: asset_server.load("bevy.png"),
             game_logo: asset_server.load("logo.png"),
             clubbo: asset_server.load("clubbo.png"),
-            satilite_idle: asset_server.load("satilite-idle.png"),
             satilite_charging: asset_server.load("satilite-charging.png"),
             laser: asset_server.load("laser.png"),
+            animation_sheets: animation::sheet_images()
+                .into_iter()
+                .map(|path| asset_server.load(path))
+                .collect(),
             heart: asset_server.load("heart.png"),
             health_crate: asset_server.load("health-crate.png"),
         }
     }
 }
 
+impl GameAssets {
+    /// Every handle, for checking how far along loading is.
+    fn handles(&self) -> Vec<UntypedHandle> {
+        let mut handles = vec![
+            self.spaceship.clone().untyped(),
+            self.crimson_spaceship.clone().untyped(),
+            self.emerald_spaceship.clone().untyped(),
+            self.gold_spaceship.clone().untyped(),
+            self.broken_spaceship.clone().untyped(),
+            self.broken_rock_astroid.clone().untyped(),
+            self.background.clone().untyped(),
+            self.nebula_background.clone().untyped(),
+            self.deep_space_background.clone().untyped(),
+            self.font.clone().untyped(),
+            self.bevy_logo.clone().untyped(),
+            self.game_logo.clone().untyped(),
+            self.clubbo.clone().untyped(),
+            self.satilite_charging.clone().untyped(),
+            self.laser.clone().untyped(),
+            self.heart.clone().untyped(),
+            self.health_crate.clone().untyped(),
+        ];
+        handles.extend(
+            self.animation_sheets
+                .iter()
+                .map(|sheet| sheet.clone().untyped()),
+        );
+        handles
+    }
+}
+
+#[cfg(test)]
+impl GameAssets {
+    /// Handles that point at nothing, for testing systems which spawn sprites without ever drawing them.
+    fn placeholder() -> Self {
+        Self {
+            spaceship: default(),
+            crimson_spaceship: default(),
+            emerald_spaceship: default(),
+            gold_spaceship: default(),
+            broken_spaceship: default(),
+            broken_rock_astroid: default(),
+            background: default(),
+            nebula_background: default(),
+            deep_space_background: default(),
+            font: default(),
+            bevy_logo: default(),
+            game_logo: default(),
+            clubbo: default(),
+            satilite_charging: default(),
+            laser: default(),
+            animation_sheets: default(),
+            heart: default(),
+            health_crate: default(),
+        }
+    }
+}
+
 #[derive(Component)]
 struct Background;
 
 fn main() {
+    let settings = settings::Settings::load();
+
     App::new()
         .insert_resource(ClearColor(Color::hex("2d1f4a").unwrap()))
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "Suborbital".to_string(),
-                resolution: WindowResolution::new(800.0, 800.0),
-                resizable: false,
+                resolution: WindowResolution::new(letterbox::PLAY_AREA, letterbox::PLAY_AREA),
+                resize_constraints: WindowResizeConstraints {
+                    min_width: letterbox::PLAY_AREA / 2.0,
+                    min_height: letterbox::PLAY_AREA / 2.0,
+                    ..default()
+                },
+                mode: settings.window_mode(),
+                present_mode: settings.present_mode(),
                 ..default()
             }),
             ..default()
         }))
+        .insert_resource(settings)
         .add_state::<AppState>()
         .init_resource::<GameAssets>()
         .add_plugins((
+            letterbox::LetterboxPlugin,
+            locale::LocalePlugin,
+            loading::LoadingPlugin,
             splash::SplashPlugin,
             menu::MenuPlugin,
+            hangar::HangarPlugin,
+            shop::ShopPlugin,
+            achievements::AchievementsScreenPlugin,
+            statistics::StatisticsPlugin,
             game::GamePlugin,
             gameover::GameOverPlugin,
+            sound::SoundPlugin,
+            music::MusicPlugin,
+            settings::SettingsPlugin,
+            pause::PausePlugin,
         ))
+        .add_plugins(animation::AnimationPlugin)
         .add_systems(Startup, setup)
         .add_systems(Update, animate_background)
         .run();
 }
 
 fn setup(mut commands: Commands, assets: Res<GameAssets>) {
-    commands.spawn(Camera2dBundle::default());
+    commands.spawn(letterbox::camera());
     commands.spawn((
         SpriteBundle {
             texture: assets.background.clone(),
@@ -103,7 +211,14 @@ fn setup(mut commands: Commands, assets: Res<GameAssets>) {
     ));
 }
 
-fn animate_background(mut background: Query<&mut Transform, With<Background>>, time: Res<Time>) {
+fn animate_background(
+    mut background: Query<&mut Transform, With<Background>>,
+    time: Res<Time>,
+    settings: Res<settings::Settings>,
+) {
+    if settings.reduced_motion {
+        return;
+    }
     let mut transform = background.single_mut();
     transform.translation.x = (time.elapsed_seconds() * PI / 60.0).cos() * 693.0;
 }
@@ -1,7 +1,13 @@
 use bevy::app::AppExit;
 use bevy::prelude::*;
 
-use crate::{utils, AppState, GameAssets};
+use crate::{
+    game::{difficulty::Difficulty, hazards::adaptive::AdaptiveDifficulty},
+    locale::Localized,
+    settings::SettingsState,
+    utils::{self, DirectionMode},
+    AppState, GameAssets,
+};
 
 #[derive(Component)]
 struct MainMenu;
@@ -9,10 +15,43 @@ struct MainMenu;
 #[derive(Component)]
 enum MenuButton {
     Play,
+    Hangar,
+    Shop,
+    Achievements,
+    Statistics,
+    Settings,
+    Difficulty,
+    Adaptive,
+    Directions,
     Quit,
     Clubbo,
 }
 
+#[derive(Component)]
+struct DifficultyLabel;
+
+#[derive(Component)]
+struct AdaptiveLabel;
+
+#[derive(Component)]
+struct DirectionsLabel;
+
+fn difficulty_label(difficulty: Difficulty) -> Localized {
+    Localized::new("menu-mode").with_message("mode", difficulty.name_key())
+}
+
+fn directions_label(direction_mode: DirectionMode) -> Localized {
+    Localized::new("menu-directions").with("count", direction_mode.directions().len())
+}
+
+fn adaptive_label(enabled: bool) -> Localized {
+    Localized::new(if enabled {
+        "menu-adaptive-on"
+    } else {
+        "menu-adaptive-off"
+    })
+}
+
 pub struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
@@ -23,11 +62,17 @@ impl Plugin for MenuPlugin {
     }
 }
 
-fn setup_menu(mut commands: Commands, assets: Res<GameAssets>) {
+fn setup_menu(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    difficulty: Res<Difficulty>,
+    adaptive: Res<AdaptiveDifficulty>,
+    direction_mode: Res<DirectionMode>,
+) {
     let button_style = Style {
         width: Val::Px(250.0),
         height: Val::Px(65.0),
-        margin: UiRect::all(Val::Px(20.0)),
+        margin: UiRect::all(Val::Px(8.0)),
         justify_content: JustifyContent::Center,
         align_items: AlignItems::Center,
         ..default()
@@ -58,7 +103,7 @@ fn setup_menu(mut commands: Commands, assets: Res<GameAssets>) {
             parent.spawn(ImageBundle {
                 style: Style {
                     width: Val::Auto,
-                    height: Val::Px(300.0),
+                    height: Val::Px(200.0),
                     ..default()
                 },
                 image: UiImage::new(assets.game_logo.clone()),
@@ -73,19 +118,99 @@ fn setup_menu(mut commands: Commands, assets: Res<GameAssets>) {
                     MenuButton::Play,
                 ))
                 .with_children(|parent| {
-                    parent.spawn(TextBundle::from_section("Play", text_style.clone()));
+                    parent.spawn((
+                        TextBundle::from_section("", text_style.clone()),
+                        Localized::new("menu-play"),
+                    ));
+                });
+            // The screens off the main menu share rows of two so everything fits in the window.
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(540.0),
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    let mut buttons = vec![
+                        (MenuButton::Hangar, "menu-hangar"),
+                        (MenuButton::Shop, "menu-shop"),
+                        (MenuButton::Achievements, "menu-achievements"),
+                        (MenuButton::Statistics, "menu-statistics"),
+                        (MenuButton::Settings, "menu-settings"),
+                    ];
+                    #[cfg(not(target_family = "wasm"))]
+                    buttons.push((MenuButton::Quit, "menu-quit"));
+                    for (button, label) in buttons {
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: button_style.clone(),
+                                    ..default()
+                                },
+                                button,
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    TextBundle::from_section(
+                                        "",
+                                        TextStyle {
+                                            font_size: 30.0,
+                                            ..text_style.clone()
+                                        },
+                                    ),
+                                    Localized::new(label),
+                                ));
+                            });
+                    }
+                });
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: button_style.clone(),
+                        ..default()
+                    },
+                    MenuButton::Difficulty,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section("", text_style.clone()),
+                        difficulty_label(*difficulty),
+                        DifficultyLabel,
+                    ));
+                });
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: button_style.clone(),
+                        ..default()
+                    },
+                    MenuButton::Adaptive,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section("", text_style.clone()),
+                        adaptive_label(adaptive.enabled),
+                        AdaptiveLabel,
+                    ));
                 });
-            #[cfg(not(target_family = "wasm"))]
             parent
                 .spawn((
                     ButtonBundle {
                         style: button_style.clone(),
                         ..default()
                     },
-                    MenuButton::Quit,
+                    MenuButton::Directions,
                 ))
                 .with_children(|parent| {
-                    parent.spawn(TextBundle::from_section("Quit", text_style.clone()));
+                    parent.spawn((
+                        TextBundle::from_section("", text_style.clone()),
+                        directions_label(*direction_mode),
+                        DirectionsLabel,
+                    ));
                 });
         });
 
@@ -116,34 +241,83 @@ fn setup_menu(mut commands: Commands, assets: Res<GameAssets>) {
                 image: UiImage::new(assets.clubbo.clone()),
                 ..default()
             });
-            parent.spawn(TextBundle::from_section(
-                "Art by Clubbo",
-                TextStyle {
-                    font: assets.font.clone(),
-                    font_size: 30.0,
-                    color: Color::WHITE,
-                },
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 30.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                Localized::new("menu-art-by"),
             ));
-            parent.spawn(TextBundle::from_section(
-                "(Click Me!)",
-                TextStyle {
-                    font: assets.font.clone(),
-                    font_size: 20.0,
-                    color: Color::WHITE,
-                },
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                Localized::new("menu-click-me"),
             ));
         });
 }
 
 fn menu_action(
     interaction_query: Query<(&Interaction, &MenuButton), (Changed<Interaction>, With<Button>)>,
+    mut difficulty_label_query: Query<
+        &mut Localized,
+        (With<DifficultyLabel>, Without<AdaptiveLabel>),
+    >,
+    mut adaptive_label_query: Query<
+        &mut Localized,
+        (With<AdaptiveLabel>, Without<DifficultyLabel>),
+    >,
+    mut directions_label_query: Query<
+        &mut Localized,
+        (
+            With<DirectionsLabel>,
+            Without<DifficultyLabel>,
+            Without<AdaptiveLabel>,
+        ),
+    >,
     mut app_state: ResMut<NextState<AppState>>,
+    mut settings_state: ResMut<NextState<SettingsState>>,
     mut app_exit_writer: EventWriter<AppExit>,
+    mut difficulty: ResMut<Difficulty>,
+    mut adaptive: ResMut<AdaptiveDifficulty>,
+    mut direction_mode: ResMut<DirectionMode>,
 ) {
     for (interaction, menu_button_action) in &interaction_query {
         if *interaction == Interaction::Pressed {
             match menu_button_action {
                 MenuButton::Play => app_state.set(AppState::Playing),
+                MenuButton::Hangar => app_state.set(AppState::Hangar),
+                MenuButton::Shop => app_state.set(AppState::Shop),
+                MenuButton::Achievements => app_state.set(AppState::Achievements),
+                MenuButton::Statistics => app_state.set(AppState::Statistics),
+                MenuButton::Settings => settings_state.set(SettingsState::Open),
+                MenuButton::Difficulty => {
+                    *difficulty = difficulty.next();
+                    for mut label in difficulty_label_query.iter_mut() {
+                        *label = difficulty_label(*difficulty);
+                    }
+                }
+                MenuButton::Adaptive => {
+                    adaptive.enabled = !adaptive.enabled;
+                    for mut label in adaptive_label_query.iter_mut() {
+                        *label = adaptive_label(adaptive.enabled);
+                    }
+                }
+                MenuButton::Directions => {
+                    *direction_mode = direction_mode.next();
+                    for mut label in directions_label_query.iter_mut() {
+                        *label = directions_label(*direction_mode);
+                    }
+                }
                 MenuButton::Quit => app_exit_writer.send(AppExit),
                 MenuButton::Clubbo => {
                     if let Err(error) =
@@ -0,0 +1,259 @@
+use std::time::Duration;
+
+use bevy::audio::{AddAudioSource, Decodable, Source, Volume};
+use bevy::prelude::*;
+
+use crate::{
+    game::{difficulty::Difficulty, hazards::HazardTimer},
+    settings::Settings,
+    sound::{Waveform, SAMPLE_RATE},
+    AppState,
+};
+
+/// How long it takes a stem to fade fully in or out, in seconds.
+const FADE_TIME: f32 = 1.5;
+
+/// How loud the music stays while the game is paused.
+const DUCKED_VOLUME: f32 = 0.3;
+
+const A2: f32 = 110.0;
+const E2: f32 = 82.41;
+const F2: f32 = 87.31;
+const G2: f32 = 98.0;
+const A3: f32 = 220.0;
+const C4: f32 = 261.63;
+const D4: f32 = 293.66;
+const E4: f32 = 329.63;
+const F4: f32 = 349.23;
+const G4: f32 = 392.0;
+const GS4: f32 = 415.3;
+const A4: f32 = 440.0;
+const B4: f32 = 493.88;
+const C5: f32 = 523.25;
+const D5: f32 = 587.33;
+const E5: f32 = 659.25;
+
+/// One looping layer of a track, a sequence of evenly spaced notes where 0 is a rest.
+#[derive(Asset, TypePath, Clone)]
+pub struct Stem {
+    waveform: Waveform,
+    notes: &'static [f32],
+    /// In seconds.
+    note_length: f32,
+    /// How sharply each note fades, higher is more plucky.
+    decay: f32,
+    volume: f32,
+}
+
+pub struct StemDecoder {
+    stem: Stem,
+    sample: u32,
+    phase: f32,
+    noise: u32,
+}
+
+impl Iterator for StemDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let note_samples = (self.stem.note_length * SAMPLE_RATE as f32) as u32;
+        let &frequency = self.stem.notes.get((self.sample / note_samples) as usize)?;
+        let progress = (self.sample % note_samples) as f32 / note_samples as f32;
+        self.sample += 1;
+
+        if frequency == 0.0 {
+            return Some(0.0);
+        }
+        self.phase = (self.phase + frequency / SAMPLE_RATE as f32).fract();
+        let envelope = (1.0 - progress).powf(self.stem.decay);
+        Some(self.stem.waveform.sample(self.phase, &mut self.noise) * envelope * self.stem.volume)
+    }
+}
+
+impl Source for StemDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(
+            self.stem.notes.len() as f32 * self.stem.note_length,
+        ))
+    }
+}
+
+impl Decodable for Stem {
+    type DecoderItem = f32;
+    type Decoder = StemDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        StemDecoder {
+            stem: self.clone(),
+            sample: 0,
+            phase: 0.0,
+            noise: 0x2545_F491,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MusicTrack {
+    Menu,
+    Game,
+    GameOver,
+}
+
+impl MusicTrack {
+    const fn of(state: AppState) -> Self {
+        match state {
+            AppState::Playing => Self::Game,
+            AppState::GameOver => Self::GameOver,
+            _ => Self::Menu,
+        }
+    }
+}
+
+/// Every stem, with the track it belongs to and how intense the game has to be before it joins in.
+/// They all loop every four seconds so they stay in time with each other.
+const STEMS: [(MusicTrack, f32, Stem); 5] = [
+    (
+        MusicTrack::Menu,
+        0.0,
+        Stem {
+            waveform: Waveform::Sine,
+            notes: &[A3, C4, E4, G4],
+            note_length: 1.0,
+            decay: 0.5,
+            volume: 0.3,
+        },
+    ),
+    (
+        MusicTrack::Game,
+        0.0,
+        Stem {
+            waveform: Waveform::Square,
+            notes: &[A2, A2, F2, F2, G2, G2, E2, E2],
+            note_length: 0.5,
+            decay: 1.0,
+            volume: 0.15,
+        },
+    ),
+    (
+        MusicTrack::Game,
+        0.4,
+        Stem {
+            waveform: Waveform::Sine,
+            notes: &[
+                A4, C5, E5, C5, F4, A4, C5, A4, G4, B4, D5, B4, E4, GS4, B4, GS4,
+            ],
+            note_length: 0.25,
+            decay: 2.0,
+            volume: 0.25,
+        },
+    ),
+    (
+        MusicTrack::Game,
+        0.75,
+        Stem {
+            waveform: Waveform::Noise,
+            notes: &[
+                0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0,
+            ],
+            note_length: 0.25,
+            decay: 6.0,
+            volume: 0.2,
+        },
+    ),
+    (
+        MusicTrack::GameOver,
+        0.0,
+        Stem {
+            waveform: Waveform::Sine,
+            notes: &[E4, D4, C4, A3],
+            note_length: 1.0,
+            decay: 1.0,
+            volume: 0.3,
+        },
+    ),
+];
+
+/// A stem that is always playing, faded in and out by changing its volume.
+#[derive(Component)]
+struct MusicStem {
+    track: MusicTrack,
+    threshold: f32,
+    level: f32,
+}
+
+pub struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_source::<Stem>()
+            .add_systems(Startup, start_music)
+            .add_systems(Update, fade_music);
+    }
+}
+
+fn start_music(mut commands: Commands, mut stems: ResMut<Assets<Stem>>) {
+    for (track, threshold, stem) in STEMS {
+        commands.spawn((
+            AudioSourceBundle {
+                source: stems.add(stem),
+                settings: PlaybackSettings::LOOP.with_volume(Volume::new_relative(0.0)),
+            },
+            MusicStem {
+                track,
+                threshold,
+                level: 0.0,
+            },
+        ));
+    }
+}
+
+/// How close the hazard interval is to its minimum, from 0 at the start of a run to 1.
+fn intensity(timer: &HazardTimer, difficulty: Difficulty) -> f32 {
+    let base_interval = difficulty.preset().base_interval;
+    ((base_interval - timer.duration().as_secs_f32()) / (base_interval - 0.5)).clamp(0.0, 1.0)
+}
+
+fn fade_music(
+    mut stems: Query<(&mut MusicStem, &AudioSink)>,
+    state: Res<State<AppState>>,
+    timer: Res<HazardTimer>,
+    difficulty: Res<Difficulty>,
+    settings: Res<Settings>,
+    virtual_time: Res<Time<Virtual>>,
+    real_time: Res<Time<Real>>,
+) {
+    let track = MusicTrack::of(*state.get());
+    let intensity = intensity(&timer, *difficulty);
+    let duck = if virtual_time.is_paused() {
+        DUCKED_VOLUME
+    } else {
+        1.0
+    };
+    let step = real_time.delta_seconds() / FADE_TIME;
+
+    for (mut stem, sink) in &mut stems {
+        let target = if stem.track == track && intensity >= stem.threshold {
+            1.0
+        } else {
+            0.0
+        };
+        stem.level = if stem.level < target {
+            (stem.level + step).min(target)
+        } else {
+            (stem.level - step).max(target)
+        };
+        sink.set_volume(stem.level * settings.volume.master * settings.volume.music * duck);
+    }
+}
@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Colour schemes for the colours that carry meaning, the standard one and one for each common colour-vision deficiency.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+pub enum Palette {
+    #[default]
+    Standard,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+/// What a colour is telling the player.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    Danger,
+    Safe,
+    Warning,
+    Scrambled,
+    SlowMotion,
+    DoubleShield,
+    DoubleScore,
+    /// The tint of ice asteroids, rock asteroids are never tinted.
+    Ice,
+}
+
+impl Palette {
+    pub const ALL: [Self; 4] = [
+        Self::Standard,
+        Self::Deuteranopia,
+        Self::Protanopia,
+        Self::Tritanopia,
+    ];
+
+    /// The key of its name in the translation files.
+    pub const fn name_key(self) -> &'static str {
+        match self {
+            Self::Standard => "palette-standard",
+            Self::Deuteranopia => "palette-deuteranopia",
+            Self::Protanopia => "palette-protanopia",
+            Self::Tritanopia => "palette-tritanopia",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&palette| palette == self);
+        Self::ALL[index.map_or(0, |index| (index + 1) % Self::ALL.len())]
+    }
+
+    pub const fn color(self, role: Role) -> Color {
+        // The red-green palettes lean on blue against orange, which both stay distinct.
+        match (self, role) {
+            (Self::Standard, Role::Danger) => Color::RED,
+            (Self::Standard, Role::Safe) => Color::DARK_GREEN,
+            (Self::Standard, Role::Warning) => Color::ORANGE,
+            (Self::Standard, Role::Scrambled) => Color::VIOLET,
+            (Self::Standard, Role::SlowMotion) => Color::CYAN,
+            (Self::Standard, Role::DoubleShield) => Color::GREEN,
+            (Self::Standard, Role::DoubleScore) => Color::GOLD,
+            (Self::Standard | Self::Deuteranopia | Self::Protanopia, Role::Ice) => Color::WHITE,
+            (Self::Deuteranopia, Role::Danger) => Color::rgb(0.84, 0.37, 0.0),
+            (Self::Protanopia, Role::Danger) => Color::rgb(1.0, 0.55, 0.0),
+            (Self::Deuteranopia | Self::Protanopia, Role::Safe) => Color::rgb(0.0, 0.45, 0.7),
+            (Self::Deuteranopia | Self::Protanopia, Role::Warning) => Color::rgb(0.94, 0.89, 0.26),
+            (Self::Deuteranopia | Self::Protanopia, Role::Scrambled) => Color::rgb(0.8, 0.47, 0.65),
+            (Self::Deuteranopia | Self::Protanopia, Role::SlowMotion) => {
+                Color::rgb(0.34, 0.71, 0.91)
+            }
+            (Self::Deuteranopia | Self::Protanopia, Role::DoubleShield) => {
+                Color::rgb(0.0, 0.45, 0.7)
+            }
+            (Self::Deuteranopia | Self::Protanopia, Role::DoubleScore) => {
+                Color::rgb(0.94, 0.89, 0.26)
+            }
+            // Blue and yellow are the ones to avoid here, so lean on red against teal instead.
+            (Self::Tritanopia, Role::Danger) => Color::rgb(0.86, 0.1, 0.1),
+            (Self::Tritanopia, Role::Safe) => Color::rgb(0.0, 0.55, 0.55),
+            (Self::Tritanopia, Role::Warning) => Color::rgb(1.0, 0.45, 0.7),
+            (Self::Tritanopia, Role::Scrambled) => Color::rgb(0.5, 0.5, 0.5),
+            (Self::Tritanopia, Role::SlowMotion) => Color::rgb(0.0, 0.75, 0.75),
+            (Self::Tritanopia, Role::DoubleShield) => Color::rgb(0.86, 0.1, 0.1),
+            (Self::Tritanopia, Role::DoubleScore) => Color::rgb(1.0, 0.6, 0.8),
+            (Self::Tritanopia, Role::Ice) => Color::rgb(1.0, 0.6, 0.9),
+        }
+    }
+}
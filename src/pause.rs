@@ -0,0 +1,147 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::{
+    game::PauseState, locale::Localized, settings::SettingsState, utils, AppState, GameAssets,
+};
+
+#[derive(Component)]
+struct PauseMenu;
+
+#[derive(Component)]
+enum PauseButton {
+    Resume,
+    Settings,
+    Quit,
+}
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(PauseState::Paused), (setup_pause_menu, pause_time))
+            .add_systems(
+                Update,
+                (
+                    toggle_pause.run_if(
+                        in_state(AppState::Playing).and_then(in_state(SettingsState::Closed)),
+                    ),
+                    pause_action.run_if(in_state(PauseState::Paused)),
+                ),
+            )
+            .add_systems(
+                OnExit(PauseState::Paused),
+                (utils::despawn_with::<PauseMenu>, unpause_time),
+            );
+    }
+}
+
+fn toggle_pause(
+    input: Res<Input<KeyCode>>,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    if input.just_pressed(KeyCode::Escape) {
+        next_pause_state.set(match pause_state.get() {
+            PauseState::Running => PauseState::Paused,
+            PauseState::Paused => PauseState::Running,
+        });
+    }
+}
+
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unpause_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn setup_pause_menu(mut commands: Commands, assets: Res<GameAssets>) {
+    let button_style = Style {
+        width: Val::Px(250.0),
+        height: Val::Px(65.0),
+        margin: UiRect::all(Val::Px(8.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    let text_style = TextStyle {
+        font: assets.font.clone(),
+        font_size: 40.0,
+        color: Color::BLACK,
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(15),
+                ..default()
+            },
+            PauseMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                        ..text_style.clone()
+                    },
+                ),
+                Localized::new("pause-title"),
+            ));
+            for (button, label) in [
+                (PauseButton::Resume, "pause-resume"),
+                (PauseButton::Settings, "pause-settings"),
+                (PauseButton::Quit, "back-to-title"),
+            ] {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: button_style.clone(),
+                            ..default()
+                        },
+                        button,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section("", text_style.clone()),
+                            Localized::new(label),
+                        ));
+                    });
+            }
+        });
+}
+
+fn pause_action(
+    interaction_query: Query<(&Interaction, &PauseButton), (Changed<Interaction>, With<Button>)>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut pause_state: ResMut<NextState<PauseState>>,
+    mut settings_state: ResMut<NextState<SettingsState>>,
+) {
+    for (interaction, pause_button_action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match pause_button_action {
+                PauseButton::Resume => pause_state.set(PauseState::Running),
+                PauseButton::Settings => settings_state.set(SettingsState::Open),
+                PauseButton::Quit => {
+                    app_state.set(AppState::Menu);
+                    pause_state.set(PauseState::Running);
+                }
+            }
+        }
+    }
+}
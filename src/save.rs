@@ -0,0 +1,128 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(not(target_family = "wasm"))]
+fn save_path(name: &str, extension: &str) -> Option<std::path::PathBuf> {
+    let dirs = directories::ProjectDirs::from("", "", "Suborbital")?;
+    Some(dirs.data_dir().join(format!("{name}.{extension}")))
+}
+
+/// Reads `name` from the save directory, falling back to the default if it is missing or invalid.
+#[cfg(not(target_family = "wasm"))]
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> T {
+    let Some(path) = save_path(name, "json") else {
+        return T::default();
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|error| {
+            bevy::log::error!("Failed to parse {}: {}", path.display(), error);
+            T::default()
+        }),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => T::default(),
+        Err(error) => {
+            bevy::log::error!("Failed to read {}: {}", path.display(), error);
+            T::default()
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+pub fn store<T: Serialize>(name: &str, value: &T) {
+    let Some(path) = save_path(name, "json") else {
+        return;
+    };
+
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| {
+            let contents = serde_json::to_string_pretty(value)?;
+            std::fs::write(&path, contents)
+        });
+    if let Err(error) = result {
+        bevy::log::error!("Failed to write {}: {}", path.display(), error);
+    }
+}
+
+/// Reads every entry appended to the log `name`, skipping any that are invalid.
+#[cfg(not(target_family = "wasm"))]
+pub fn load_log<T: DeserializeOwned>(name: &str) -> Vec<T> {
+    let Some(path) = save_path(name, "jsonl") else {
+        return Vec::new();
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => contents
+            .lines()
+            .filter_map(|line| {
+                serde_json::from_str(line)
+                    .map_err(|error| {
+                        bevy::log::error!("Failed to parse entry in {}: {}", path.display(), error);
+                    })
+                    .ok()
+            })
+            .collect(),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(error) => {
+            bevy::log::error!("Failed to read {}: {}", path.display(), error);
+            Vec::new()
+        }
+    }
+}
+
+/// Adds `value` to the end of the log `name`, one entry per line, without rewriting what's already there.
+#[cfg(not(target_family = "wasm"))]
+pub fn append<T: Serialize>(name: &str, value: &T) {
+    use std::io::Write;
+
+    let Some(path) = save_path(name, "jsonl") else {
+        return;
+    };
+
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| {
+            let mut line = serde_json::to_string(value)?;
+            line.push('\n');
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)?
+                .write_all(line.as_bytes())
+        });
+    if let Err(error) = result {
+        bevy::log::error!("Failed to write {}: {}", path.display(), error);
+    }
+}
+
+/// Seconds since the Unix epoch.
+#[cfg(not(target_family = "wasm"))]
+pub fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+// There is no file system on the web, so progress only lasts for the session.
+#[cfg(target_family = "wasm")]
+pub fn load<T: DeserializeOwned + Default>(_name: &str) -> T {
+    T::default()
+}
+
+#[cfg(target_family = "wasm")]
+pub fn store<T: Serialize>(_name: &str, _value: &T) {}
+
+#[cfg(target_family = "wasm")]
+pub fn load_log<T: DeserializeOwned>(_name: &str) -> Vec<T> {
+    Vec::new()
+}
+
+#[cfg(target_family = "wasm")]
+pub fn append<T: Serialize>(_name: &str, _value: &T) {}
+
+// `SystemTime` isn't available on the web either.
+#[cfg(target_family = "wasm")]
+pub fn unix_time() -> u64 {
+    0
+}
@@ -0,0 +1,592 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy::ui::{FocusPolicy, RelativeCursorPosition};
+use bevy::window::{PresentMode, PrimaryWindow, WindowMode};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    locale::{Language, Localized},
+    palette::Palette,
+    save,
+    sound::SoundVolume,
+    utils, GameAssets,
+};
+
+/// The fastest anything may blink with photosensitivity safe mode on, in half-cycles per second.
+/// This keeps it to two flashes a second, under the usual limit of three.
+const SAFE_BLINK_RATE: f32 = 4.0;
+
+/// The settings screen sits on top of whatever opened it, so it has its own state rather than an `AppState`.
+#[derive(States, Default, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum SettingsState {
+    #[default]
+    Closed,
+    Open,
+}
+
+/// The keys for turning, the arrow keys always work as well.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct KeyBindings {
+    pub turn_counterclockwise: KeyCode,
+    pub turn_clockwise: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            turn_counterclockwise: KeyCode::A,
+            turn_clockwise: KeyCode::D,
+        }
+    }
+}
+
+/// Options from the settings screen, saved between sessions.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub volume: SoundVolume,
+    pub screen_shake: bool,
+    pub fullscreen: bool,
+    pub vsync: bool,
+    pub keys: KeyBindings,
+    pub language: Language,
+    pub palette: Palette,
+    /// Adds a glyph to every hazard and tool, so they can be told apart by shape as well as colour.
+    pub shape_cues: bool,
+    /// Stops shaking and background scrolling, and holds blinking things steady.
+    pub reduced_motion: bool,
+    /// Slows down blinking and dims the laser beam.
+    pub photosensitivity_safe: bool,
+    /// How fast hazards move and time passes for them, from 0.5 to 1. Anything under 1 is the slow mode assist.
+    pub game_speed: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            volume: SoundVolume::default(),
+            screen_shake: true,
+            fullscreen: false,
+            vsync: true,
+            keys: KeyBindings::default(),
+            language: Language::default(),
+            palette: Palette::default(),
+            shape_cues: false,
+            reduced_motion: false,
+            photosensitivity_safe: false,
+            game_speed: 1.0,
+        }
+    }
+}
+
+impl Settings {
+    pub const fn window_mode(&self) -> WindowMode {
+        if self.fullscreen {
+            WindowMode::BorderlessFullscreen
+        } else {
+            WindowMode::Windowed
+        }
+    }
+
+    pub const fn shake(&self) -> bool {
+        self.screen_shake && !self.reduced_motion
+    }
+
+    /// Whether something blinking at `rate` half-cycles per second is in its off phase after `elapsed` seconds.
+    pub fn blink_off(&self, elapsed: f32, rate: f32) -> bool {
+        if self.reduced_motion {
+            return false;
+        }
+        let rate = if self.photosensitivity_safe {
+            rate.min(SAFE_BLINK_RATE)
+        } else {
+            rate
+        };
+        (elapsed * rate * PI).sin() > 0.0
+    }
+
+    /// Loads the saved settings, keeping every slider within its range in case the file was edited by hand.
+    pub fn load() -> Self {
+        save::load::<Self>("settings").clamped()
+    }
+
+    fn clamped(mut self) -> Self {
+        for slider in Slider::ALL {
+            let (min, max) = slider.range();
+            let value = slider.get_mut(&mut self);
+            *value = value.clamp(min, max);
+        }
+        self
+    }
+
+    pub const fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+}
+
+#[derive(Component)]
+struct SettingsScreen;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum Slider {
+    Master,
+    Music,
+    Effects,
+    GameSpeed,
+}
+
+impl Slider {
+    const ALL: [Self; 4] = [Self::Master, Self::Music, Self::Effects, Self::GameSpeed];
+
+    /// The key of its name in the translation files.
+    const fn name_key(self) -> &'static str {
+        match self {
+            Self::Master => "settings-master",
+            Self::Music => "settings-music",
+            Self::Effects => "settings-effects",
+            Self::GameSpeed => "settings-speed",
+        }
+    }
+
+    /// The values at either end of the slider.
+    const fn range(self) -> (f32, f32) {
+        match self {
+            Self::GameSpeed => (0.5, 1.0),
+            _ => (0.0, 1.0),
+        }
+    }
+
+    const fn get(self, settings: &Settings) -> f32 {
+        match self {
+            Self::Master => settings.volume.master,
+            Self::Music => settings.volume.music,
+            Self::Effects => settings.volume.effects,
+            Self::GameSpeed => settings.game_speed,
+        }
+    }
+
+    fn get_mut(self, settings: &mut Settings) -> &mut f32 {
+        match self {
+            Self::Master => &mut settings.volume.master,
+            Self::Music => &mut settings.volume.music,
+            Self::Effects => &mut settings.volume.effects,
+            Self::GameSpeed => &mut settings.game_speed,
+        }
+    }
+
+    /// How far along the slider is filled, from 0 to 1.
+    fn fill(self, settings: &Settings) -> f32 {
+        let (min, max) = self.range();
+        (self.get(settings) - min) / (max - min)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Binding {
+    TurnCounterclockwise,
+    TurnClockwise,
+}
+
+impl Binding {
+    /// The key of its name in the translation files.
+    const fn name_key(self) -> &'static str {
+        match self {
+            Self::TurnCounterclockwise => "settings-turn-left",
+            Self::TurnClockwise => "settings-turn-right",
+        }
+    }
+
+    const fn get(self, keys: &KeyBindings) -> KeyCode {
+        match self {
+            Self::TurnCounterclockwise => keys.turn_counterclockwise,
+            Self::TurnClockwise => keys.turn_clockwise,
+        }
+    }
+
+    fn get_mut(self, keys: &mut KeyBindings) -> &mut KeyCode {
+        match self {
+            Self::TurnCounterclockwise => &mut keys.turn_counterclockwise,
+            Self::TurnClockwise => &mut keys.turn_clockwise,
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum SettingsButton {
+    ScreenShake,
+    Fullscreen,
+    Vsync,
+    Bind(Binding),
+    Language,
+    Palette,
+    ShapeCues,
+    ReducedMotion,
+    PhotosensitivitySafe,
+    Back,
+}
+
+/// Text that shows the current value of a setting.
+#[derive(Component, Clone, Copy)]
+enum SettingLabel {
+    Slider(Slider),
+    Button(SettingsButton),
+}
+
+#[derive(Component)]
+struct SliderFill(Slider);
+
+/// The binding waiting for a key press, if any.
+#[derive(Resource, Default)]
+struct Rebinding(Option<Binding>);
+
+fn label(label: SettingLabel, settings: &Settings, rebinding: &Rebinding) -> Localized {
+    let on_off = |key: &'static str, enabled: bool| {
+        Localized::new(key).with_message(
+            "state",
+            if enabled {
+                "settings-on"
+            } else {
+                "settings-off"
+            },
+        )
+    };
+    match label {
+        SettingLabel::Slider(slider) => Localized::new(slider.name_key())
+            .with("percent", format!("{:.0}", slider.get(settings) * 100.0)),
+        SettingLabel::Button(button) => match button {
+            SettingsButton::ScreenShake => on_off("settings-shake", settings.screen_shake),
+            SettingsButton::Fullscreen => on_off("settings-fullscreen", settings.fullscreen),
+            SettingsButton::Vsync => on_off("settings-vsync", settings.vsync),
+            SettingsButton::Bind(binding) if rebinding.0 == Some(binding) => {
+                Localized::new(binding.name_key()).with("key", "...")
+            }
+            SettingsButton::Bind(binding) => Localized::new(binding.name_key())
+                .with("key", format!("{:?}", binding.get(&settings.keys))),
+            SettingsButton::Language => {
+                Localized::new("settings-language").with("language", settings.language.name())
+            }
+            SettingsButton::Palette => Localized::new(settings.palette.name_key()),
+            SettingsButton::ShapeCues => on_off("settings-shapes", settings.shape_cues),
+            SettingsButton::ReducedMotion => {
+                on_off("settings-less-motion", settings.reduced_motion)
+            }
+            SettingsButton::PhotosensitivitySafe => {
+                on_off("settings-safe-flashes", settings.photosensitivity_safe)
+            }
+            SettingsButton::Back => Localized::new("back"),
+        },
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<SettingsState>()
+            .init_resource::<Rebinding>()
+            .add_systems(OnEnter(SettingsState::Open), setup_settings)
+            .add_systems(
+                Update,
+                (
+                    (settings_action, drag_sliders, rebind_keys, update_labels)
+                        .chain()
+                        .run_if(in_state(SettingsState::Open)),
+                    apply_settings.run_if(resource_changed::<Settings>()),
+                ),
+            )
+            .add_systems(
+                OnExit(SettingsState::Open),
+                (utils::despawn_with::<SettingsScreen>, save_settings),
+            );
+    }
+}
+
+fn setup_settings(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    settings: Res<Settings>,
+    rebinding: Res<Rebinding>,
+) {
+    let button_style = Style {
+        width: Val::Px(250.0),
+        height: Val::Px(65.0),
+        margin: UiRect::all(Val::Px(8.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    let text_style = TextStyle {
+        font: assets.font.clone(),
+        font_size: 30.0,
+        color: Color::BLACK,
+    };
+
+    let white_text_style = TextStyle {
+        color: Color::WHITE,
+        ..text_style.clone()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: Color::hex("2d1f4a").unwrap().with_a(0.95).into(),
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(20),
+                ..default()
+            },
+            SettingsScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 60.0,
+                        ..white_text_style.clone()
+                    },
+                ),
+                Localized::new("settings-title"),
+            ));
+            for slider in [
+                Slider::Master,
+                Slider::Music,
+                Slider::Effects,
+                Slider::GameSpeed,
+            ] {
+                let fill = slider.fill(&settings);
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            margin: UiRect::all(Val::Px(8.0)),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        let text_label = SettingLabel::Slider(slider);
+                        parent.spawn((
+                            TextBundle::from_section("", white_text_style.clone()).with_style(
+                                Style {
+                                    width: Val::Px(200.0),
+                                    ..default()
+                                },
+                            ),
+                            label(text_label, &settings, &rebinding),
+                            text_label,
+                        ));
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        width: Val::Px(300.0),
+                                        height: Val::Px(30.0),
+                                        border: UiRect::all(Val::Px(2.0)),
+                                        ..default()
+                                    },
+                                    border_color: Color::WHITE.into(),
+                                    background_color: Color::NONE.into(),
+                                    ..default()
+                                },
+                                slider,
+                                RelativeCursorPosition::default(),
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    NodeBundle {
+                                        style: Style {
+                                            width: Val::Percent(fill * 100.0),
+                                            height: Val::Percent(100.0),
+                                            ..default()
+                                        },
+                                        background_color: Color::GOLD.into(),
+                                        focus_policy: FocusPolicy::Pass,
+                                        ..default()
+                                    },
+                                    SliderFill(slider),
+                                ));
+                            });
+                    });
+            }
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(540.0),
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for button in [
+                        SettingsButton::ScreenShake,
+                        SettingsButton::Fullscreen,
+                        SettingsButton::Vsync,
+                        SettingsButton::Language,
+                        SettingsButton::Palette,
+                        SettingsButton::ShapeCues,
+                        SettingsButton::ReducedMotion,
+                        SettingsButton::PhotosensitivitySafe,
+                        SettingsButton::Bind(Binding::TurnCounterclockwise),
+                        SettingsButton::Bind(Binding::TurnClockwise),
+                        SettingsButton::Back,
+                    ] {
+                        let text_label = SettingLabel::Button(button);
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: button_style.clone(),
+                                    ..default()
+                                },
+                                button,
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    TextBundle::from_section("", text_style.clone()),
+                                    label(text_label, &settings, &rebinding),
+                                    text_label,
+                                ));
+                            });
+                    }
+                });
+        });
+}
+
+fn settings_action(
+    interaction_query: Query<(&Interaction, &SettingsButton), (Changed<Interaction>, With<Button>)>,
+    mut settings: ResMut<Settings>,
+    mut rebinding: ResMut<Rebinding>,
+    mut settings_state: ResMut<NextState<SettingsState>>,
+) {
+    for (interaction, settings_button_action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match settings_button_action {
+                SettingsButton::ScreenShake => settings.screen_shake = !settings.screen_shake,
+                SettingsButton::Fullscreen => settings.fullscreen = !settings.fullscreen,
+                SettingsButton::Vsync => settings.vsync = !settings.vsync,
+                SettingsButton::Bind(binding) => rebinding.0 = Some(*binding),
+                SettingsButton::Language => settings.language = settings.language.next(),
+                SettingsButton::Palette => settings.palette = settings.palette.next(),
+                SettingsButton::ShapeCues => settings.shape_cues = !settings.shape_cues,
+                SettingsButton::ReducedMotion => {
+                    settings.reduced_motion = !settings.reduced_motion;
+                }
+                SettingsButton::PhotosensitivitySafe => {
+                    settings.photosensitivity_safe = !settings.photosensitivity_safe;
+                }
+                SettingsButton::Back => settings_state.set(SettingsState::Closed),
+            }
+        }
+    }
+}
+
+/// Sets the value from wherever the slider is being held.
+fn drag_sliders(
+    sliders: Query<(&Interaction, &Slider, &RelativeCursorPosition)>,
+    mut settings: ResMut<Settings>,
+) {
+    for (interaction, slider, cursor) in &sliders {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let Some(position) = cursor.normalized {
+            let (min, max) = slider.range();
+            let fill = (position.x * 20.0).round().clamp(0.0, 20.0) / 20.0;
+            let value = fill.mul_add(max - min, min);
+            let setting = slider.get_mut(settings.bypass_change_detection());
+            if *setting != value {
+                *setting = value;
+                settings.set_changed();
+            }
+        }
+    }
+}
+
+fn rebind_keys(
+    input: Res<Input<KeyCode>>,
+    mut settings: ResMut<Settings>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    let Some(binding) = rebinding.0 else {
+        return;
+    };
+    let Some(&key) = input.get_just_pressed().next() else {
+        return;
+    };
+    if key != KeyCode::Escape {
+        *binding.get_mut(&mut settings.keys) = key;
+    }
+    rebinding.0 = None;
+}
+
+fn update_labels(
+    settings: Res<Settings>,
+    rebinding: Res<Rebinding>,
+    mut labels: Query<(&mut Localized, &SettingLabel)>,
+    mut fills: Query<(&mut Style, &SliderFill)>,
+) {
+    if !settings.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+    for (mut localized, &text_label) in &mut labels {
+        localized.set_if_neq(label(text_label, &settings, &rebinding));
+    }
+    for (mut style, fill) in &mut fills {
+        style.width = Val::Percent(fill.0.fill(&settings) * 100.0);
+    }
+}
+
+fn apply_settings(settings: Res<Settings>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    for mut window in &mut windows {
+        window.mode = settings.window_mode();
+        window.present_mode = settings.present_mode();
+    }
+}
+
+fn save_settings(settings: Res<Settings>, mut rebinding: ResMut<Rebinding>) {
+    rebinding.0 = None;
+    save::store("settings", &*settings);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamped_keeps_sliders_in_range() {
+        let mut settings = Settings::default();
+        settings.volume.master = 3.0;
+        settings.volume.music = -1.0;
+        settings.volume.effects = 0.25;
+        settings.game_speed = 0.1;
+
+        let settings = settings.clamped();
+        assert_eq!(settings.volume.master, 1.0);
+        assert_eq!(settings.volume.music, 0.0);
+        assert_eq!(settings.volume.effects, 0.25);
+        assert_eq!(settings.game_speed, 0.5);
+    }
+
+    #[test]
+    fn clamped_leaves_defaults_alone() {
+        let settings = Settings::default().clamped();
+        for slider in Slider::ALL {
+            assert_eq!(slider.get(&settings), slider.get(&Settings::default()));
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+
+use crate::{
+    game::progression::{Progress, Unlock},
+    locale::Localized,
+    save, utils, AppState, GameAssets,
+};
+
+#[derive(Component)]
+struct Shop;
+
+#[derive(Component)]
+enum ShopButton {
+    /// Buys the unlock if it isn't owned yet, otherwise equips or unequips it.
+    Item(Unlock),
+    Back,
+}
+
+#[derive(Component)]
+struct ItemLabel(Unlock);
+
+#[derive(Component)]
+struct CurrencyLabel;
+
+fn item_label(unlock: Unlock, progress: &Progress) -> Localized {
+    let label = Localized::new("shop-item").with_message("item", unlock.name_key());
+    if progress.is_equipped(unlock) {
+        label.with_message("status", "shop-equipped")
+    } else if progress.owns(unlock) {
+        label.with_message("status", "shop-owned")
+    } else {
+        label.with("status", unlock.price())
+    }
+}
+
+fn currency_label(progress: &Progress) -> Localized {
+    Localized::new("shop-stardust").with("amount", progress.currency)
+}
+
+pub struct ShopPlugin;
+
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Shop), setup_shop)
+            .add_systems(Update, shop_action.run_if(in_state(AppState::Shop)))
+            .add_systems(
+                OnExit(AppState::Shop),
+                (utils::despawn_with::<Shop>, save_progress),
+            );
+    }
+}
+
+fn setup_shop(mut commands: Commands, assets: Res<GameAssets>, progress: Res<Progress>) {
+    let item_style = Style {
+        width: Val::Px(500.0),
+        height: Val::Px(40.0),
+        margin: UiRect::all(Val::Px(3.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    let text_style = TextStyle {
+        font: assets.font.clone(),
+        font_size: 40.0,
+        color: Color::BLACK,
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            Shop,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                Localized::new("shop-title"),
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 30.0,
+                        color: Color::GOLD,
+                    },
+                ),
+                currency_label(&progress),
+                CurrencyLabel,
+            ));
+            for unlock in Unlock::ALL {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: item_style.clone(),
+                            ..default()
+                        },
+                        ShopButton::Item(unlock),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "",
+                                TextStyle {
+                                    font_size: 24.0,
+                                    ..text_style.clone()
+                                },
+                            ),
+                            item_label(unlock, &progress),
+                            ItemLabel(unlock),
+                        ));
+                    });
+            }
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                Localized::new("shop-help"),
+            ));
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(250.0),
+                            height: Val::Px(65.0),
+                            margin: UiRect::all(Val::Px(20.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    ShopButton::Back,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section("", text_style.clone()),
+                        Localized::new("back"),
+                    ));
+                });
+        });
+}
+
+fn shop_action(
+    interaction_query: Query<(&Interaction, &ShopButton), (Changed<Interaction>, With<Button>)>,
+    mut item_labels: Query<(&ItemLabel, &mut Localized), Without<CurrencyLabel>>,
+    mut currency_labels: Query<&mut Localized, (With<CurrencyLabel>, Without<ItemLabel>)>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut progress: ResMut<Progress>,
+) {
+    for (interaction, shop_button_action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match shop_button_action {
+                ShopButton::Item(unlock) => {
+                    if progress.buy(*unlock) {
+                        progress.toggle(*unlock);
+                    }
+                    // Equipping an item can unequip another in the same slot, so refresh them all.
+                    for (label, mut localized) in item_labels.iter_mut() {
+                        localized.set_if_neq(item_label(label.0, &progress));
+                    }
+                    for mut localized in currency_labels.iter_mut() {
+                        localized.set_if_neq(currency_label(&progress));
+                    }
+                }
+                ShopButton::Back => app_state.set(AppState::Menu),
+            }
+        }
+    }
+}
+
+fn save_progress(progress: Res<Progress>) {
+    save::store("progress", &*progress);
+}
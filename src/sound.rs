@@ -0,0 +1,370 @@
+use std::time::Duration;
+
+use bevy::audio::{AddAudioSource, Decodable, Source, Volume};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{
+        hazards::{HazardType, SatiliteState},
+        spaceship::HitOutcomeEvent,
+    },
+    settings::Settings,
+    AppState,
+};
+
+pub const SAMPLE_RATE: u32 = 44100;
+
+/// How long a sound can go without being picked up before we decide there's nothing to play it on.
+const OUTPUT_TIMEOUT: f32 = 1.0;
+
+#[derive(Clone, Copy)]
+pub enum Waveform {
+    Sine,
+    Square,
+    Noise,
+}
+
+impl Waveform {
+    /// The value at `phase`, which runs from 0 to 1 over each cycle.
+    pub fn sample(self, phase: f32, noise: &mut u32) -> f32 {
+        match self {
+            Self::Sine => (phase * std::f32::consts::TAU).sin(),
+            Self::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Self::Noise => {
+                // A cheap xorshift is plenty for hiss.
+                *noise ^= *noise << 13;
+                *noise ^= *noise >> 17;
+                *noise ^= *noise << 5;
+                *noise as f32 / u32::MAX as f32 * 2.0 - 1.0
+            }
+        }
+    }
+}
+
+/// A short generated tone that sweeps from one frequency to another while fading out.
+#[derive(Asset, TypePath, Clone)]
+pub struct Synth {
+    waveform: Waveform,
+    start_frequency: f32,
+    end_frequency: f32,
+    /// In seconds.
+    duration: f32,
+}
+
+impl Synth {
+    const fn new(
+        waveform: Waveform,
+        start_frequency: f32,
+        end_frequency: f32,
+        duration: f32,
+    ) -> Self {
+        Self {
+            waveform,
+            start_frequency,
+            end_frequency,
+            duration,
+        }
+    }
+}
+
+pub struct SynthDecoder {
+    synth: Synth,
+    sample: u32,
+    phase: f32,
+    noise: u32,
+}
+
+impl Iterator for SynthDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let progress = self.sample as f32 / (self.synth.duration * SAMPLE_RATE as f32);
+        if progress >= 1.0 {
+            return None;
+        }
+        self.sample += 1;
+
+        let frequency = self.synth.start_frequency
+            + (self.synth.end_frequency - self.synth.start_frequency) * progress;
+        self.phase = (self.phase + frequency / SAMPLE_RATE as f32).fract();
+
+        let value = self.synth.waveform.sample(self.phase, &mut self.noise);
+
+        Some(value * (1.0 - progress) * 0.5)
+    }
+}
+
+impl Source for SynthDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(self.synth.duration))
+    }
+}
+
+impl Decodable for Synth {
+    type DecoderItem = f32;
+    type Decoder = SynthDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        SynthDecoder {
+            synth: self.clone(),
+            sample: 0,
+            phase: 0.0,
+            noise: 0x9E37_79B9,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundEffect {
+    Drill,
+    Melt,
+    Block,
+    Charge,
+    Damage,
+    Collect,
+    GameOver,
+    Click,
+}
+
+impl SoundEffect {
+    const ALL: [Self; 8] = [
+        Self::Drill,
+        Self::Melt,
+        Self::Block,
+        Self::Charge,
+        Self::Damage,
+        Self::Collect,
+        Self::GameOver,
+        Self::Click,
+    ];
+
+    const fn synth(self) -> Synth {
+        match self {
+            Self::Drill => Synth::new(Waveform::Noise, 0.0, 0.0, 0.25),
+            Self::Melt => Synth::new(Waveform::Sine, 900.0, 300.0, 0.3),
+            Self::Block => Synth::new(Waveform::Square, 1200.0, 1500.0, 0.12),
+            Self::Charge => Synth::new(Waveform::Sine, 200.0, 800.0, 0.6),
+            Self::Damage => Synth::new(Waveform::Square, 180.0, 60.0, 0.35),
+            Self::Collect => Synth::new(Waveform::Sine, 600.0, 1200.0, 0.2),
+            Self::GameOver => Synth::new(Waveform::Square, 400.0, 80.0, 1.2),
+            Self::Click => Synth::new(Waveform::Square, 800.0, 800.0, 0.04),
+        }
+    }
+
+    const fn category(self) -> SoundCategory {
+        match self {
+            Self::Drill | Self::Melt | Self::Block | Self::Charge => SoundCategory::Hazards,
+            Self::Damage | Self::Collect | Self::GameOver => SoundCategory::Ship,
+            Self::Click => SoundCategory::Interface,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum SoundCategory {
+    Hazards,
+    Ship,
+    Interface,
+}
+
+/// Volume multipliers for the music and sound effects, on top of the master volume.
+/// Each kind of sound effect can be adjusted in the settings file as well.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundVolume {
+    pub master: f32,
+    pub music: f32,
+    pub effects: f32,
+    pub hazards: f32,
+    pub ship: f32,
+    pub interface: f32,
+}
+
+impl Default for SoundVolume {
+    fn default() -> Self {
+        Self {
+            master: 0.8,
+            music: 0.6,
+            effects: 1.0,
+            hazards: 1.0,
+            ship: 1.0,
+            interface: 1.0,
+        }
+    }
+}
+
+impl SoundVolume {
+    pub fn of(&self, category: SoundCategory) -> f32 {
+        self.master
+            * self.effects
+            * match category {
+                SoundCategory::Hazards => self.hazards,
+                SoundCategory::Ship => self.ship,
+                SoundCategory::Interface => self.interface,
+            }
+    }
+}
+
+#[derive(Event)]
+pub struct SoundEvent(pub SoundEffect);
+
+#[derive(Resource)]
+struct SoundEffects(Vec<(SoundEffect, Handle<Synth>)>);
+
+/// Set once a sound never starts, which means there is no audio device to play on.
+#[derive(Resource, Default)]
+struct AudioUnavailable(bool);
+
+/// Marks a sound that was just spawned, so it can be cleaned up if nothing picks it up.
+#[derive(Component)]
+struct PendingSound(Timer);
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_source::<Synth>()
+            .add_event::<SoundEvent>()
+            .init_resource::<AudioUnavailable>()
+            .add_systems(Startup, create_sounds)
+            .add_systems(OnEnter(AppState::GameOver), game_over_sound)
+            .add_systems(
+                Update,
+                (
+                    (
+                        hit_sounds,
+                        charge_sounds.run_if(in_state(AppState::Playing)),
+                        click_sounds,
+                    ),
+                    play_sounds,
+                    check_output,
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn create_sounds(mut commands: Commands, mut synths: ResMut<Assets<Synth>>) {
+    commands.insert_resource(SoundEffects(
+        SoundEffect::ALL
+            .into_iter()
+            .map(|effect| (effect, synths.add(effect.synth())))
+            .collect(),
+    ));
+}
+
+fn hit_sounds(
+    mut outcome_events: EventReader<HitOutcomeEvent>,
+    mut sound_events: EventWriter<SoundEvent>,
+) {
+    for outcome in outcome_events.read() {
+        if outcome.damage > 0 {
+            sound_events.send(SoundEvent(SoundEffect::Damage));
+        }
+        if !outcome.success {
+            continue;
+        }
+        let effect = match outcome.hit.hazard_type {
+            HazardType::Rock | HazardType::Scrambler => SoundEffect::Drill,
+            HazardType::Ice => SoundEffect::Melt,
+            HazardType::Laser | HazardType::Missile => SoundEffect::Block,
+            HazardType::Crate => SoundEffect::Collect,
+        };
+        sound_events.send(SoundEvent(effect));
+    }
+}
+
+fn charge_sounds(
+    satilites: Query<&SatiliteState, Changed<SatiliteState>>,
+    mut sound_events: EventWriter<SoundEvent>,
+) {
+    if satilites
+        .iter()
+        .any(|state| *state == SatiliteState::Charging)
+    {
+        sound_events.send(SoundEvent(SoundEffect::Charge));
+    }
+}
+
+fn click_sounds(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
+    mut sound_events: EventWriter<SoundEvent>,
+) {
+    if interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        sound_events.send(SoundEvent(SoundEffect::Click));
+    }
+}
+
+fn game_over_sound(mut sound_events: EventWriter<SoundEvent>) {
+    sound_events.send(SoundEvent(SoundEffect::GameOver));
+}
+
+fn play_sounds(
+    mut commands: Commands,
+    mut sound_events: EventReader<SoundEvent>,
+    sounds: Res<SoundEffects>,
+    settings: Res<Settings>,
+    unavailable: Res<AudioUnavailable>,
+) {
+    if unavailable.0 {
+        sound_events.clear();
+        return;
+    }
+
+    for &SoundEvent(effect) in sound_events.read() {
+        let Some((_, handle)) = sounds.0.iter().find(|(sound, _)| *sound == effect) else {
+            continue;
+        };
+        commands.spawn((
+            AudioSourceBundle {
+                source: handle.clone(),
+                settings: PlaybackSettings::DESPAWN
+                    .with_volume(Volume::new_relative(settings.volume.of(effect.category()))),
+            },
+            PendingSound(Timer::from_seconds(OUTPUT_TIMEOUT, TimerMode::Once)),
+        ));
+    }
+}
+
+/// Sounds get an `AudioSink` once they start playing, which never happens without an audio device.
+/// In that case they'd pile up forever, so clean them up and stop making new ones.
+fn check_output(
+    mut commands: Commands,
+    mut pending: Query<(Entity, &mut PendingSound, Has<AudioSink>)>,
+    mut unavailable: ResMut<AudioUnavailable>,
+    time: Res<Time>,
+) {
+    for (entity, mut timer, playing) in &mut pending {
+        if playing {
+            commands.entity(entity).remove::<PendingSound>();
+        } else if timer.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            if !unavailable.0 {
+                warn!("Sounds aren't playing, turning sound effects off");
+                unavailable.0 = true;
+            }
+        }
+    }
+}
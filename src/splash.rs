@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::{utils, AppState, GameAssets};
+use crate::{locale::Localized, utils, AppState, GameAssets};
 
 #[derive(Component)]
 struct SplashScreen;
@@ -50,13 +50,16 @@ fn setup_splash(mut commands: Commands, assets: Res<GameAssets>) {
                 image: UiImage::new(assets.bevy_logo.clone()),
                 ..default()
             });
-            parent.spawn(TextBundle::from_section(
-                "Made with Bevy",
-                TextStyle {
-                    font: assets.font.clone(),
-                    font_size: 40.0,
-                    color: Color::WHITE,
-                },
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 40.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                Localized::new("splash-made-with-bevy"),
             ));
         });
 
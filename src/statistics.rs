@@ -0,0 +1,184 @@
+use bevy::prelude::*;
+
+use crate::{game::history::Lifetime, locale::Localized, utils, AppState, GameAssets};
+
+/// The most bars the score graph shows, older runs get averaged together beyond this.
+const GRAPH_BARS: usize = 50;
+
+#[derive(Component)]
+struct StatisticsScreen;
+
+#[derive(Component)]
+enum StatisticsButton {
+    Back,
+}
+
+pub struct StatisticsPlugin;
+
+impl Plugin for StatisticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Statistics), setup_statistics)
+            .add_systems(
+                Update,
+                statistics_action.run_if(in_state(AppState::Statistics)),
+            )
+            .add_systems(
+                OnExit(AppState::Statistics),
+                utils::despawn_with::<StatisticsScreen>,
+            );
+    }
+}
+
+fn setup_statistics(mut commands: Commands, assets: Res<GameAssets>) {
+    let lifetime = Lifetime::load();
+
+    let text_style = TextStyle {
+        font: assets.font.clone(),
+        font_size: 30.0,
+        color: Color::WHITE,
+    };
+
+    let play_time = lifetime.total_play_time as u32;
+    let killer = Localized::new("statistics-killer");
+    let lines = [
+        Localized::new("statistics-runs").with("count", lifetime.runs.len()),
+        Localized::new("statistics-play-time")
+            .with("hours", play_time / 3600)
+            .with("minutes", format!("{:02}", play_time / 60 % 60))
+            .with("seconds", format!("{:02}", play_time % 60)),
+        Localized::new("statistics-median-score").with(
+            "score",
+            lifetime
+                .median_score
+                .map_or_else(|| "-".to_string(), |score| score.to_string()),
+        ),
+        match lifetime.most_common_killer {
+            Some(hazard_type) => killer.with_message("hazard", hazard_type.name_key()),
+            None => killer.with("hazard", "-"),
+        },
+    ];
+
+    // Group runs into at most `GRAPH_BARS` buckets, each showing its average score.
+    let bucket_size = lifetime.runs.len().div_ceil(GRAPH_BARS).max(1);
+    let bars: Vec<f32> = lifetime
+        .runs
+        .chunks(bucket_size)
+        .map(|bucket| bucket.iter().map(|run| run.score as f32).sum::<f32>() / bucket.len() as f32)
+        .collect();
+    let highest = bars.iter().copied().fold(1.0, f32::max);
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            StatisticsScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 60.0,
+                        ..text_style.clone()
+                    },
+                ),
+                Localized::new("statistics-title"),
+            ));
+            for line in lines {
+                parent.spawn((TextBundle::from_section("", text_style.clone()), line));
+            }
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        ..text_style.clone()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::top(Val::Px(20.0)),
+                    ..default()
+                }),
+                Localized::new("statistics-score-over-time").with("best", format!("{highest:.0}")),
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(600.0),
+                        height: Val::Px(250.0),
+                        align_items: AlignItems::FlexEnd,
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    border_color: Color::WHITE.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for score in &bars {
+                        parent.spawn(NodeBundle {
+                            style: Style {
+                                flex_grow: 1.0,
+                                height: Val::Percent(score / highest * 100.0),
+                                margin: UiRect::horizontal(Val::Px(1.0)),
+                                ..default()
+                            },
+                            background_color: Color::GOLD.into(),
+                            ..default()
+                        });
+                    }
+                });
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(250.0),
+                            height: Val::Px(65.0),
+                            margin: UiRect::all(Val::Px(20.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    StatisticsButton::Back,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font_size: 40.0,
+                                color: Color::BLACK,
+                                ..text_style.clone()
+                            },
+                        ),
+                        Localized::new("back"),
+                    ));
+                });
+        });
+}
+
+fn statistics_action(
+    interaction_query: Query<
+        (&Interaction, &StatisticsButton),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, statistics_button_action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match statistics_button_action {
+                StatisticsButton::Back => app_state.set(AppState::Menu),
+            }
+        }
+    }
+}
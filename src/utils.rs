@@ -1,7 +1,8 @@
-use std::f32::consts::PI;
+use std::f32::consts::{FRAC_1_SQRT_2, FRAC_PI_4};
 
 use bevy::prelude::*;
-use rand::{distributions::Standard, prelude::Distribution, Rng};
+use rand::{distributions::Standard, prelude::Distribution, seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
 
 pub fn despawn_with<T: Component>(to_despawn: Query<Entity, With<T>>, mut commands: Commands) {
     for entity in &to_despawn {
@@ -9,14 +10,20 @@ pub fn despawn_with<T: Component>(to_despawn: Query<Entity, With<T>>, mut comman
     }
 }
 
-#[derive(Component, Clone, Copy, PartialEq, Eq)]
+/// Which way something is facing or travelling, in counter-clockwise order starting from up.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub enum Direction {
     Up,
+    UpLeft,
     Left,
+    DownLeft,
     Down,
+    DownRight,
     Right,
+    UpRight,
 }
 
+/// Only ever picks one of the four sides, see [`DirectionMode`] for the diagonals.
 impl Distribution<Direction> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Direction {
         match rng.gen_range(0..4) {
@@ -29,41 +36,134 @@ impl Distribution<Direction> for Standard {
 }
 
 impl Direction {
+    pub const ALL: [Self; 4] = [Self::Up, Self::Left, Self::Down, Self::Right];
+    pub const ALL_EIGHT: [Self; 8] = [
+        Self::Up,
+        Self::UpLeft,
+        Self::Left,
+        Self::DownLeft,
+        Self::Down,
+        Self::DownRight,
+        Self::Right,
+        Self::UpRight,
+    ];
+
+    /// How many eighths of a turn counter-clockwise from up.
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Turns by `eighths` eighths of a turn counter-clockwise.
+    pub const fn rotate_ccw_by(self, eighths: usize) -> Self {
+        Self::ALL_EIGHT[(self.index() + eighths) % 8]
+    }
+
+    /// Turns by `eighths` eighths of a turn clockwise.
+    pub const fn rotate_cw_by(self, eighths: usize) -> Self {
+        self.rotate_ccw_by(8 - eighths % 8)
+    }
+
     pub const fn rotate_cw(self) -> Self {
-        match self {
-            Self::Up => Self::Right,
-            Self::Left => Self::Up,
-            Self::Down => Self::Left,
-            Self::Right => Self::Down,
-        }
+        self.rotate_cw_by(2)
     }
 
     pub const fn rotate_ccw(self) -> Self {
-        match self {
-            Self::Up => Self::Left,
-            Self::Left => Self::Down,
-            Self::Down => Self::Right,
-            Self::Right => Self::Up,
-        }
+        self.rotate_ccw_by(2)
+    }
+
+    pub const fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
     }
 
     pub fn to_quat(self) -> Quat {
-        Quat::from_rotation_z(
-            PI * match self {
-                Self::Up => 0.0,
-                Self::Left => 0.5,
-                Self::Down => 1.0,
-                Self::Right => 1.5,
-            },
-        )
+        Quat::from_rotation_z(FRAC_PI_4 * self.index() as f32)
     }
 
     pub const fn to_vec3(self) -> Vec3 {
         match self {
             Self::Up => Vec3::Y,
+            Self::UpLeft => Vec3::new(-FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0),
             Self::Left => Vec3::NEG_X,
+            Self::DownLeft => Vec3::new(-FRAC_1_SQRT_2, -FRAC_1_SQRT_2, 0.0),
             Self::Down => Vec3::NEG_Y,
+            Self::DownRight => Vec3::new(FRAC_1_SQRT_2, -FRAC_1_SQRT_2, 0.0),
             Self::Right => Vec3::X,
+            Self::UpRight => Vec3::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0),
+        }
+    }
+}
+
+/// Whether hazards only come from the four sides, or from the diagonals as well.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum DirectionMode {
+    #[default]
+    Four,
+    Eight,
+}
+
+impl DirectionMode {
+    pub const fn directions(self) -> &'static [Direction] {
+        match self {
+            Self::Four => &Direction::ALL,
+            Self::Eight => &Direction::ALL_EIGHT,
+        }
+    }
+
+    /// How far the spaceship turns with each key press, in eighths of a turn.
+    pub const fn turn_step(self) -> usize {
+        match self {
+            Self::Four => 2,
+            Self::Eight => 1,
+        }
+    }
+
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Four => Self::Eight,
+            Self::Eight => Self::Four,
+        }
+    }
+}
+
+impl Distribution<Direction> for DirectionMode {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Direction {
+        *self.directions().choose(rng).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotations_go_round_the_sides() {
+        assert_eq!(Direction::Up.rotate_cw(), Direction::Right);
+        assert_eq!(Direction::Up.rotate_ccw(), Direction::Left);
+        assert_eq!(Direction::Right.rotate_cw(), Direction::Down);
+        assert_eq!(Direction::UpLeft.rotate_cw_by(1), Direction::Up);
+        assert_eq!(Direction::UpRight.rotate_ccw_by(1), Direction::Up);
+    }
+
+    #[test]
+    fn rotations_wrap_and_undo_each_other() {
+        for direction in Direction::ALL_EIGHT {
+            assert_eq!(direction.rotate_cw_by(8), direction);
+            assert_eq!(direction.rotate_ccw_by(8), direction);
+            assert_eq!(direction.rotate_cw_by(10), direction.rotate_cw());
+            for eighths in 0..8 {
+                assert_eq!(
+                    direction.rotate_cw_by(eighths).rotate_ccw_by(eighths),
+                    direction
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn only_in_between_directions_are_diagonal() {
+        for direction in Direction::ALL {
+            assert!(!direction.is_diagonal());
+            assert!(direction.rotate_cw_by(1).is_diagonal());
         }
     }
 }